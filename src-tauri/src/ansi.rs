@@ -0,0 +1,141 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Write colored cells as ANSI escape sequences, sending a color only when it changes, and read
+//! rendered frames back into styled runs for formats that are not terminals.
+
+use crate::palette::{self, Color, Palette};
+
+pub type Rgb = [u8; 3];
+
+const RESET: &[u8] = b"\x1b[0m";
+
+/// Tracks the colors the terminal already has so runs of equal cells cost one escape.
+/// Colors are compared after snapping to the palette, so neighbours that snap alike share an escape too.
+#[derive(Default)]
+pub struct AnsiWriter {
+    palette: Palette,
+    fg: Option<Color>,
+    bg: Option<Color>,
+}
+
+impl AnsiWriter {
+    pub fn new(palette: Palette) -> Self {
+        Self { palette, ..Self::default() }
+    }
+
+    /// Two stacked pixels in one cell: `▀` in `top` over `bottom`, or `▄` when swapped colors are already set.
+    pub fn half_block(&mut self, top: Rgb, bottom: Rgb, out: &mut Vec<u8>) {
+        if self.is_set(bottom, top) { self.block('▄', bottom, top, out); } else { self.block('▀', top, bottom, out); }
+    }
+
+    /// Whether `fg` over `bg` is what the terminal is already drawing with.
+    pub fn is_set(&self, fg: Rgb, bg: Rgb) -> bool {
+        self.fg == Some(self.palette.snap(fg)) && self.bg == Some(self.palette.snap(bg))
+    }
+
+    pub fn block(&mut self, glyph: char, fg: Rgb, bg: Rgb, out: &mut Vec<u8>) {
+        self.set_fg(fg, out);
+        self.set_bg(bg, out);
+        let mut utf8 = [0u8; 4];
+        out.extend_from_slice(glyph.encode_utf8(&mut utf8).as_bytes());
+    }
+
+    /// A character in `fg` over whatever background the terminal has.
+    pub fn glyph(&mut self, glyph: &[u8], fg: Rgb, out: &mut Vec<u8>) {
+        self.set_fg(fg, out);
+        out.extend_from_slice(glyph);
+    }
+
+    /// A cell of one color, drawn as a space so only the background matters.
+    pub fn blank(&mut self, bg: Rgb, out: &mut Vec<u8>) {
+        self.set_bg(bg, out);
+        out.push(b' ');
+    }
+
+    /// Reset before every line break so a terminal never paints colored margins.
+    pub fn end_row(&mut self, out: &mut Vec<u8>) {
+        if self.fg.is_some() || self.bg.is_some() { out.extend_from_slice(RESET); }
+        *self = Self::new(self.palette);
+        out.push(b'\n');
+    }
+
+    fn set_fg(&mut self, rgb: Rgb, out: &mut Vec<u8>) {
+        let color = self.palette.snap(rgb);
+        if self.fg == Some(color) { return; }
+        color.write(false, out);
+        self.fg = Some(color);
+    }
+
+    fn set_bg(&mut self, rgb: Rgb, out: &mut Vec<u8>) {
+        let color = self.palette.snap(rgb);
+        if self.bg == Some(color) { return; }
+        color.write(true, out);
+        self.bg = Some(color);
+    }
+}
+
+/// Colors in effect for a run of text; `None` is the viewer's default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    pub fg: Option<Rgb>,
+    pub bg: Option<Rgb>,
+}
+
+/// Text drawn in one style.
+pub struct Run {
+    pub style: Style,
+    pub text: String,
+}
+
+/// Split a rendered frame into rows of runs, merging neighbours the escapes left in the same colors.
+/// Understands the SGR codes `AnsiWriter` writes; anything else is dropped.
+pub fn styled_rows(frame: &str) -> Vec<Vec<Run>> {
+    let mut style = Style::default();
+    let mut rows = Vec::new();
+    for line in frame.lines() {
+        let mut runs: Vec<Run> = Vec::new();
+        let mut rest = line;
+        while !rest.is_empty() {
+            if let Some(escape) = rest.strip_prefix("\x1b[") {
+                let end = escape.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(escape.len());
+                if escape[end..].starts_with('m') { apply_sgr(&escape[..end], &mut style); }
+                rest = escape.get(end + 1..).unwrap_or("");
+                continue;
+            }
+            // At least one character, so a stray ESC that starts no sequence is kept as text.
+            let first = rest.chars().next().map_or(1, char::len_utf8);
+            let end = rest[first..].find('\x1b').map_or(rest.len(), |i| i + first);
+            match runs.last_mut() {
+                Some(run) if run.style == style => run.text.push_str(&rest[..end]),
+                _ => runs.push(Run { style, text: rest[..end].to_string() }),
+            }
+            rest = &rest[end..];
+        }
+        rows.push(runs);
+    }
+    rows
+}
+
+fn apply_sgr(params: &str, style: &mut Style) {
+    let mut codes = params.split(';').map(|code| code.parse::<u16>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        match code {
+            0 => *style = Style::default(),
+            30..=37 => style.fg = Some(palette::indexed_color(code as u8 - 30)),
+            90..=97 => style.fg = Some(palette::indexed_color(code as u8 - 82)),
+            40..=47 => style.bg = Some(palette::indexed_color(code as u8 - 40)),
+            100..=107 => style.bg = Some(palette::indexed_color(code as u8 - 92)),
+            39 => style.fg = None,
+            49 => style.bg = None,
+            38 | 48 => {
+                let color = match codes.next() {
+                    Some(5) => codes.next().map(|index| palette::indexed_color(index as u8)),
+                    Some(2) => Some([(); 3].map(|_| codes.next().unwrap_or(0) as u8)),
+                    _ => None,
+                };
+                if code == 38 { style.fg = color; } else { style.bg = color; }
+            }
+            _ => {}
+        }
+    }
+}
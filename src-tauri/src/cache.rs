@@ -0,0 +1,430 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Build grayscale and color tensors per output width on first use and keep the recently used ones within a memory budget.
+
+use crate::media::{self, SourceFrames};
+use crate::progress::LoadMonitor;
+use image::imageops::{self, FilterType};
+use image::{ImageBuffer, Pixel};
+use ndarray::{Array3, ArrayViewMut2, Axis};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Condvar, Mutex};
+
+pub const MIN_WIDTH: u32 = 20;
+pub const MAX_WIDTH: u32 = 250;
+
+/// How many widths on either side of the requested one get built in the background.
+const PREWARM_RADIUS: u32 = 2;
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheConfig {
+    /// Covers the tensors built from the source; the decoded frames themselves are held outside it.
+    pub budget_bytes: usize,
+    pub prewarm: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self { budget_bytes: 512 * 1024 * 1024, prewarm: true }
+    }
+}
+
+/// How source pixels are reduced to one gray value per character cell.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Resample {
+    /// One source pixel per cell; fastest, but thin lines can fall between samples.
+    #[default]
+    Nearest,
+    /// Mean of every pixel the cell covers.
+    Box,
+    Bilinear,
+    Lanczos3,
+    /// Darkest pixel in the cell, so dark strokes on a light background survive downscaling.
+    Min,
+    /// Brightest pixel in the cell, for light strokes on a dark background.
+    Max,
+}
+
+/// Width over height of one character cell, held in thousandths so it can be part of a hash key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CellAspect(u32);
+
+impl CellAspect {
+    /// Cells twice as tall as wide, which is close to most terminal fonts.
+    pub const DEFAULT: Self = Self(500);
+
+    pub fn new(ratio: f32) -> Result<Self, String> {
+        if !(0.1..=4.0).contains(&ratio) { return Err(format!("Cell aspect {} is outside 0.1-4.0", ratio)); }
+        Ok(Self((ratio * 1000.0).round() as u32))
+    }
+
+    pub fn ratio(self) -> f32 {
+        self.0 as f32 / 1000.0
+    }
+}
+
+impl Default for CellAspect {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Samples kept per character cell; brightness-only modes read one, shape-aware modes a small patch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SubCells {
+    pub cols: u32,
+    pub rows: u32,
+}
+
+impl SubCells {
+    pub const ONE: Self = Self { cols: 1, rows: 1 };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub width: u32,
+    pub filter: Resample,
+    pub cell_aspect: CellAspect,
+    pub grid: SubCells,
+    /// Build the color tensor instead, with each sample's RGB interleaved along the last axis.
+    pub rgb: bool,
+}
+
+struct Entry {
+    tensor: Arc<Array3<u8>>,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Entries {
+    map: HashMap<CacheKey, Entry>,
+    tick: u64,
+    /// Total size of the cached tensors.
+    bytes: usize,
+    /// Keys some caller is building right now; others wait for them instead of building a duplicate.
+    building: HashSet<CacheKey>,
+}
+
+/// What `claim` found for a key.
+enum Claim<'a> {
+    Cached(Arc<Array3<u8>>),
+    /// The caller builds the key; dropping this releases it to anyone waiting.
+    Build(Building<'a>),
+    Busy,
+}
+
+struct Building<'a> {
+    cache: &'a WidthCache,
+    key: CacheKey,
+}
+
+impl Drop for Building<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut entries) = self.cache.entries.lock() { entries.building.remove(&self.key); }
+        self.cache.built.notify_all();
+    }
+}
+
+pub struct WidthCache {
+    frames: SourceFrames,
+    config: Mutex<CacheConfig>,
+    entries: Mutex<Entries>,
+    /// Signalled whenever a key leaves `Entries::building`.
+    built: Condvar,
+}
+
+impl WidthCache {
+    pub fn new(frames: SourceFrames, config: CacheConfig) -> Self {
+        Self { frames, config: Mutex::new(config), entries: Mutex::new(Entries::default()), built: Condvar::new() }
+    }
+
+    pub fn configure(&self, config: CacheConfig) -> Result<(), String> {
+        *self.config.lock().map_err(|_| "Lock failed")? = config;
+        let mut entries = self.entries.lock().map_err(|_| "Lock failed")?;
+        evict(&mut entries, config.budget_bytes, None);
+        Ok(())
+    }
+
+    /// Return the tensor for `key`, building it outside the lock on a miss, or waiting for
+    /// the caller already building it.
+    pub fn get(&self, key: CacheKey) -> Result<Arc<Array3<u8>>, String> {
+        if !(MIN_WIDTH..=MAX_WIDTH).contains(&key.width) {
+            return Err(format!("Width must be between {} and {}", MIN_WIDTH, MAX_WIDTH));
+        }
+        let building = loop {
+            match self.claim(key)? {
+                Claim::Cached(tensor) => return Ok(tensor),
+                Claim::Build(building) => break building,
+                Claim::Busy => self.wait_for(key)?,
+            }
+        };
+        let tensor = self.insert(key, Arc::new(self.build(key)?));
+        drop(building);
+        tensor
+    }
+
+    fn build(&self, key: CacheKey) -> Result<Array3<u8>, String> {
+        let dimensions = self.frames.dimensions();
+        let tensor = match (&self.frames, key.rgb) {
+            (SourceFrames::Gray(frames), false) => {
+                build_tensor(frames.len(), dimensions, key, |i| Cow::Borrowed(&frames[i]))
+            }
+            (SourceFrames::Rgb(frames), true) => {
+                build_tensor(frames.len(), dimensions, key, |i| Cow::Borrowed(&frames[i]))
+            }
+            // Luma is derived a frame at a time, so only the frames being reduced exist in gray.
+            (SourceFrames::Rgb(frames), false) => {
+                build_tensor(frames.len(), dimensions, key, |i| Cow::Owned(media::luma_from_rgb(&frames[i])))
+            }
+            // Gray-only sources repeat each sample across the three channels.
+            (SourceFrames::Gray(_), true) => {
+                let luma = self.get(CacheKey { rgb: false, ..key })?;
+                let (frames, h, w) = luma.dim();
+                Array3::from_shape_fn((frames, h, w * 3), |(f, y, x)| luma[[f, y, x / 3]])
+            }
+        };
+        Ok(tensor)
+    }
+
+    /// Build `keys` while a load is still in progress, reporting each one.
+    pub fn warm(&self, keys: &[CacheKey], monitor: &LoadMonitor) -> Result<(), String> {
+        monitor.cached(0, keys.len())?;
+        for (built, &key) in keys.iter().enumerate() {
+            self.get(key)?;
+            monitor.cached(built + 1, keys.len())?;
+        }
+        Ok(())
+    }
+
+    /// Build the neighbouring widths of `key` on the rayon pool so slider drags land on warm entries.
+    /// Skipped once the budget is spent, where each new neighbour would only evict an entry in use,
+    /// and for keys that are cached or already being built.
+    pub fn prewarm(self: &Arc<Self>, key: CacheKey) {
+        let prewarm = self.config.lock().map(|config| config.prewarm).unwrap_or(false);
+        if !prewarm { return; }
+        let lo = key.width.saturating_sub(PREWARM_RADIUS).max(MIN_WIDTH);
+        let hi = (key.width + PREWARM_RADIUS).min(MAX_WIDTH);
+        let cache = Arc::clone(self);
+        rayon::spawn(move || {
+            for width in (lo..=hi).filter(|&w| w != key.width) {
+                let neighbour = CacheKey { width, ..key };
+                let budget = cache.config.lock().map(|config| config.budget_bytes).unwrap_or(0);
+                let full = cache.entries.lock().map(|entries| entries.bytes >= budget).unwrap_or(true);
+                if full { continue; }
+                let Ok(Claim::Build(building)) = cache.claim(neighbour) else { continue };
+                let _ = cache.build(neighbour).and_then(|tensor| cache.insert(neighbour, Arc::new(tensor)));
+                drop(building);
+            }
+        });
+    }
+
+    /// Return the cached tensor for `key`, or claim the key for the caller to build unless someone else has.
+    fn claim(&self, key: CacheKey) -> Result<Claim<'_>, String> {
+        let mut entries = self.entries.lock().map_err(|_| "Lock failed")?;
+        entries.tick += 1;
+        let tick = entries.tick;
+        if let Some(entry) = entries.map.get_mut(&key) {
+            entry.last_used = tick;
+            return Ok(Claim::Cached(Arc::clone(&entry.tensor)));
+        }
+        if !entries.building.insert(key) { return Ok(Claim::Busy); }
+        Ok(Claim::Build(Building { cache: self, key }))
+    }
+
+    /// Block until nobody is building `key`.
+    fn wait_for(&self, key: CacheKey) -> Result<(), String> {
+        let entries = self.entries.lock().map_err(|_| "Lock failed")?;
+        let _entries = self.built.wait_while(entries, |entries| entries.building.contains(&key)).map_err(|_| "Lock failed")?;
+        Ok(())
+    }
+
+    fn insert(&self, key: CacheKey, tensor: Arc<Array3<u8>>) -> Result<Arc<Array3<u8>>, String> {
+        let budget = self.config.lock().map_err(|_| "Lock failed")?.budget_bytes;
+        let mut entries = self.entries.lock().map_err(|_| "Lock failed")?;
+        entries.tick += 1;
+        let tick = entries.tick;
+        entries.bytes += tensor.len();
+        entries.map.insert(key, Entry { tensor: Arc::clone(&tensor), last_used: tick });
+        evict(&mut entries, budget, Some(key));
+        Ok(tensor)
+    }
+}
+
+/// Drop least recently used tensors until under budget, never evicting `keep`.
+fn evict(entries: &mut Entries, budget: usize, keep: Option<CacheKey>) {
+    while entries.bytes > budget {
+        let victim = entries.map.iter()
+            .filter(|(&key, _)| Some(key) != keep)
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(&key, _)| key);
+        let Some(victim) = victim else { break };
+        if let Some(entry) = entries.map.remove(&victim) { entries.bytes -= entry.tensor.len(); }
+    }
+}
+
+/// Reduce `count` frames of `dimensions`, each fetched with `frame`, to `key.width` columns, with as many rows
+/// as the cell shape needs to keep proportions, and `key.grid` samples per cell. Multi-channel pixels are
+/// interleaved along the last axis.
+fn build_tensor<'a, P>(
+    count: usize,
+    (orig_w, orig_h): (u32, u32),
+    key: CacheKey,
+    frame: impl Fn(usize) -> Cow<'a, ImageBuffer<P, Vec<u8>>> + Sync
+) -> Array3<u8>
+where
+    P: Pixel<Subpixel = u8> + Send + Sync + 'static
+{
+    let channels = P::CHANNEL_COUNT as u32;
+    let aspect_ratio = orig_h as f32 / orig_w as f32;
+    let rows = ((key.width as f32 * aspect_ratio * key.cell_aspect.ratio()) as u32).max(1);
+    let w = key.width * key.grid.cols;
+    let h = rows * key.grid.rows;
+    let mut tensor = Array3::<u8>::zeros((count, h as usize, (w * channels) as usize));
+    tensor.axis_iter_mut(Axis(0)).into_par_iter().enumerate().for_each(|(i, mut plane)| {
+        let image = frame(i);
+        let image = image.as_ref();
+        match key.filter {
+            Resample::Nearest => {
+                let pixels = image.as_raw();
+                for y in 0..h {
+                    let src_y = (y * orig_h / h) * orig_w;
+                    for x in 0..w {
+                        let src = ((src_y + x * orig_w / w) * channels) as usize;
+                        for c in 0..channels as usize {
+                            plane[[y as usize, (x * channels) as usize + c]] = pixels[src + c];
+                        }
+                    }
+                }
+            }
+            Resample::Bilinear => copy_resized(&mut plane, image, FilterType::Triangle),
+            Resample::Lanczos3 => copy_resized(&mut plane, image, FilterType::Lanczos3),
+            Resample::Box => reduce_cells(&mut plane, image, |cell| {
+                let sum: u32 = cell.iter().map(|&p| p as u32).sum();
+                (sum / cell.len() as u32) as u8
+            }),
+            Resample::Min => reduce_cells(&mut plane, image, |cell| cell.iter().copied().min().unwrap_or(255)),
+            Resample::Max => reduce_cells(&mut plane, image, |cell| cell.iter().copied().max().unwrap_or(0)),
+        }
+    });
+    tensor
+}
+
+/// `imageops::resize` widens its kernel when shrinking, so these filters antialias on their own.
+fn copy_resized<P>(plane: &mut ArrayViewMut2<u8>, image: &ImageBuffer<P, Vec<u8>>, filter: FilterType)
+where
+    P: Pixel<Subpixel = u8> + 'static
+{
+    let (h, w) = (plane.dim().0, plane.dim().1 / P::CHANNEL_COUNT as usize);
+    let resized = imageops::resize(image, w as u32, h as u32, filter);
+    for (out, &value) in plane.iter_mut().zip(resized.as_raw()) { *out = value; }
+}
+
+/// Gather each channel of the source pixels under each cell (at least one) and collapse them with `reduce`.
+fn reduce_cells<P>(plane: &mut ArrayViewMut2<u8>, image: &ImageBuffer<P, Vec<u8>>, reduce: impl Fn(&[u8]) -> u8)
+where
+    P: Pixel<Subpixel = u8>
+{
+    let channels = P::CHANNEL_COUNT as usize;
+    let (h, w) = (plane.dim().0, plane.dim().1 / channels);
+    let (orig_w, orig_h) = (image.width() as usize, image.height() as usize);
+    let pixels = image.as_raw();
+    let mut cell = Vec::new();
+    for y in 0..h {
+        let y0 = y * orig_h / h;
+        let y1 = ((y + 1) * orig_h / h).max(y0 + 1);
+        for x in 0..w {
+            let x0 = x * orig_w / w;
+            let x1 = ((x + 1) * orig_w / w).max(x0 + 1);
+            for c in 0..channels {
+                cell.clear();
+                for row in y0..y1 {
+                    let start = (row * orig_w + x0) * channels + c;
+                    let end = (row * orig_w + x1) * channels;
+                    cell.extend(pixels[start..end].iter().step_by(channels));
+                }
+                plane[[y, x * channels + c]] = reduce(&cell);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::GrayImage;
+
+    fn key(width: u32) -> CacheKey {
+        CacheKey { width, filter: Resample::Nearest, cell_aspect: CellAspect::DEFAULT, grid: SubCells::ONE, rgb: false }
+    }
+
+    /// Four 100x100 gray frames, 40 000 bytes of source.
+    fn cache(budget_bytes: usize) -> WidthCache {
+        let frames = (0..4).map(|i| GrayImage::from_pixel(100, 100, image::Luma([i * 60]))).collect();
+        WidthCache::new(SourceFrames::Gray(frames), CacheConfig { budget_bytes, prewarm: false })
+    }
+
+    #[test]
+    fn source_frames_do_not_count_against_the_budget() {
+        // Widths 20 and 40 make 800 and 3200 byte tensors, together well under the source's size.
+        let cache = cache(4000);
+        let (small, large) = (cache.get(key(20)).unwrap(), cache.get(key(40)).unwrap());
+        for _ in 0..3 {
+            assert!(Arc::ptr_eq(&small, &cache.get(key(20)).unwrap()));
+            assert!(Arc::ptr_eq(&large, &cache.get(key(40)).unwrap()));
+        }
+    }
+
+    #[test]
+    fn concurrent_gets_share_one_build() {
+        let cache = cache(usize::MAX);
+        let barrier = std::sync::Barrier::new(2);
+        let (a, b) = std::thread::scope(|scope| {
+            let get = || {
+                barrier.wait();
+                cache.get(key(120)).unwrap()
+            };
+            let a = scope.spawn(get);
+            let b = scope.spawn(get);
+            (a.join().unwrap(), b.join().unwrap())
+        });
+        assert!(Arc::ptr_eq(&a, &b));
+    }
+
+    #[test]
+    fn waiters_take_the_builders_tensor() {
+        let cache = cache(usize::MAX);
+        let Ok(Claim::Build(building)) = cache.claim(key(20)) else { panic!("the first claim builds") };
+        assert!(matches!(cache.claim(key(20)), Ok(Claim::Busy)));
+        let (waited, built) = std::thread::scope(|scope| {
+            let waiter = scope.spawn(|| cache.get(key(20)).unwrap());
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            let built = cache.insert(key(20), Arc::new(cache.build(key(20)).unwrap())).unwrap();
+            drop(building);
+            (waiter.join().unwrap(), built)
+        });
+        assert!(Arc::ptr_eq(&waited, &built));
+    }
+
+    #[test]
+    fn evicts_least_recently_used_but_never_keep() {
+        let mut entries = Entries::default();
+        for (width, last_used) in [(20, 3), (21, 1), (22, 2)] {
+            entries.map.insert(key(width), Entry { tensor: Arc::new(Array3::zeros((1, 1, 10))), last_used });
+            entries.bytes += 10;
+        }
+        evict(&mut entries, 20, None);
+        assert!(!entries.map.contains_key(&key(21)));
+        assert_eq!(entries.bytes, 20);
+
+        evict(&mut entries, 10, Some(key(22)));
+        assert!(entries.map.contains_key(&key(22)) && !entries.map.contains_key(&key(20)));
+
+        evict(&mut entries, 0, Some(key(22)));
+        assert_eq!((entries.map.len(), entries.bytes), (1, 10));
+    }
+}
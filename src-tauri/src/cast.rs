@@ -0,0 +1,85 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Write asciinema v2 casts: a JSON header line, then one timed output event per frame that
+//! homes the cursor and redraws the whole frame.
+
+use crate::export::{FrameSink, Header};
+use serde_json::json;
+use std::io::{self, Write};
+
+/// Hide the cursor and clear the screen before the first frame.
+const START: &str = "\x1b[?25l\x1b[2J";
+const HOME: &str = "\x1b[H";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+
+/// Records one playthrough; looping is left to the player (`loop` in asciinema-player).
+pub struct CastSink<W: Write> {
+    out: W,
+    /// When the next frame appears, in milliseconds from the start.
+    clock_ms: u64,
+}
+
+impl<W: Write> CastSink<W> {
+    pub fn open(header: &Header, mut out: W) -> io::Result<Self> {
+        let header = json!({
+            "version": 2,
+            "width": header.columns,
+            "height": header.rows,
+            "title": header.source,
+            "env": { "TERM": "xterm-256color" },
+        });
+        writeln!(out, "{}", header)?;
+        Ok(Self { out, clock_ms: 0 })
+    }
+
+    fn event(&mut self, data: &str) -> io::Result<()> {
+        writeln!(self.out, "{}", json!([self.clock_ms as f64 / 1000.0, "o", data]))
+    }
+}
+
+impl<W: Write> FrameSink for CastSink<W> {
+    fn frame(&mut self, index: usize, delay_ms: u32, text: &[u8]) -> io::Result<()> {
+        // Terminals need a carriage return with each line feed; the last row gets neither,
+        // so a frame exactly as tall as the terminal does not scroll it.
+        let rows = String::from_utf8_lossy(text);
+        let rows = rows.strip_suffix('\n').unwrap_or(&rows).replace('\n', "\r\n");
+        let start = if index == 0 { START } else { "" };
+        self.event(&format!("{}{}{}", start, HOME, rows))?;
+        self.clock_ms += delay_ms as u64;
+        Ok(())
+    }
+
+    /// The closing event keeps the last frame up for its full delay.
+    fn finish(&mut self) -> io::Result<()> {
+        self.event(SHOW_CURSOR)?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media::{LoopCount, Timing};
+    use crate::render::RenderSettings;
+    use serde_json::Value;
+
+    #[test]
+    fn writes_a_v2_header_and_timed_events() {
+        let timing = Timing { delays_ms: vec![40, 60], loop_count: LoopCount::Forever };
+        let settings = RenderSettings::default();
+        let header = Header { columns: 2, rows: 2, frame_count: 2, timing: &timing, source: "clip.gif", settings: &settings };
+        let mut out = Vec::new();
+        let mut sink = CastSink::open(&header, &mut out).unwrap();
+        sink.frame(0, 40, b"ab\ncd\n").unwrap();
+        sink.frame(1, 60, b"ef\ngh\n").unwrap();
+        sink.finish().unwrap();
+
+        let lines: Vec<Value> = String::from_utf8(out).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines, [
+            json!({ "version": 2, "width": 2, "height": 2, "title": "clip.gif", "env": { "TERM": "xterm-256color" } }),
+            json!([0.0, "o", "\x1b[?25l\x1b[2J\x1b[Hab\r\ncd"]),
+            json!([0.04, "o", "\x1b[Hef\r\ngh"]),
+            json!([0.1, "o", "\x1b[?25h"]),
+        ]);
+    }
+}
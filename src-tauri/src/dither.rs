@@ -0,0 +1,158 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Hide quantization error, by diffusing it to neighbouring pixels or by ordered threshold patterns,
+//! so gradients survive being snapped to a few glyphs or palette colors.
+
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+
+/// How values are snapped to the output levels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Dither {
+    /// Snap every value on its own.
+    #[default]
+    None,
+    FloydSteinberg,
+    /// Diffuses only three quarters of the error, keeping contrast at the cost of some midtone accuracy.
+    Atkinson,
+    JarvisJudiceNinke,
+    /// Ordered dithering with a 4x4 Bayer matrix; the pattern is fixed, so animations never crawl.
+    Bayer4,
+    Bayer8,
+}
+
+/// Error diffusion taps as `(dx, dy, weight)`, with the weights' divisor.
+struct Kernel {
+    taps: &'static [(isize, usize, f32)],
+    divisor: f32,
+}
+
+const FLOYD_STEINBERG: Kernel = Kernel { taps: &[(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)], divisor: 16.0 };
+
+const ATKINSON: Kernel = Kernel {
+    taps: &[(1, 0, 1.0), (2, 0, 1.0), (-1, 1, 1.0), (0, 1, 1.0), (1, 1, 1.0), (0, 2, 1.0)],
+    divisor: 8.0,
+};
+
+const JARVIS_JUDICE_NINKE: Kernel = Kernel {
+    taps: &[
+        (1, 0, 7.0), (2, 0, 5.0),
+        (-2, 1, 3.0), (-1, 1, 5.0), (0, 1, 7.0), (1, 1, 5.0), (2, 1, 3.0),
+        (-2, 2, 1.0), (-1, 2, 3.0), (0, 2, 5.0), (1, 2, 3.0), (2, 2, 1.0),
+    ],
+    divisor: 48.0,
+};
+
+/// Side of the tiles that stable error diffusion stays within.
+const STABLE_TILE: usize = 8;
+
+impl Dither {
+    /// Snap every pixel with `quantize`, which returns the output for a pixel and the value that output stands for.
+    /// `spread` is the usual gap between neighbouring levels, which ordered patterns scale their offsets to.
+    /// With `stable`, error diffusion never crosses fixed tile borders, so a change only disturbs the pattern
+    /// in its own tiles and still areas of an animation dither the same in every frame.
+    pub fn apply<T, const N: usize>(
+        self,
+        mut values: Array2<[f32; N]>,
+        spread: f32,
+        stable: bool,
+        quantize: impl Fn([f32; N]) -> (T, [f32; N])
+    ) -> Array2<T> {
+        let kernel = match self {
+            Self::None => return values.map(|&value| quantize(value).0),
+            Self::Bayer4 => return ordered(&values, 2, spread, quantize),
+            Self::Bayer8 => return ordered(&values, 3, spread, quantize),
+            Self::FloydSteinberg => &FLOYD_STEINBERG,
+            Self::Atkinson => &ATKINSON,
+            Self::JarvisJudiceNinke => &JARVIS_JUDICE_NINKE,
+        };
+        let (h, w) = values.dim();
+        let tile = if stable { STABLE_TILE } else { usize::MAX };
+        let mut out = Vec::with_capacity(h * w);
+        for y in 0..h {
+            for x in 0..w {
+                let old = values[[y, x]];
+                let (snapped, level) = quantize(old);
+                out.push(snapped);
+                for &(dx, dy, weight) in kernel.taps {
+                    let (nx, ny) = (x.wrapping_add_signed(dx), y + dy);
+                    if nx >= w || ny >= h || nx / tile != x / tile || ny / tile != y / tile { continue; }
+                    let neighbour = &mut values[[ny, nx]];
+                    for c in 0..N { neighbour[c] += (old[c] - level[c]) * weight / kernel.divisor; }
+                }
+            }
+        }
+        Array2::from_shape_vec((h, w), out).expect("one output per pixel")
+    }
+}
+
+/// Offset each pixel by its entry in a `2^bits` square Bayer matrix before snapping it.
+fn ordered<T, const N: usize>(
+    values: &Array2<[f32; N]>,
+    bits: u32,
+    spread: f32,
+    quantize: impl Fn([f32; N]) -> (T, [f32; N])
+) -> Array2<T> {
+    let cells = (1usize << (2 * bits)) as f32;
+    Array2::from_shape_fn(values.dim(), |(y, x)| {
+        let offset = ((bayer_rank(x, y, bits) as f32 + 0.5) / cells - 0.5) * spread;
+        quantize(values[[y, x]].map(|v| v + offset)).0
+    })
+}
+
+/// Position of `(x, y)` in the threshold order of a `2^bits` Bayer matrix, built by recursively
+/// splitting each square into quadrants visited top-left, bottom-right, top-right, bottom-left.
+fn bayer_rank(x: usize, y: usize, bits: u32) -> usize {
+    (0..bits).fold(0, |rank, bit| {
+        let (xb, yb) = ((x >> bit) & 1, (y >> bit) & 1);
+        rank * 4 + 2 * (xb ^ yb) + yb
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diffused(kernel: &Kernel) -> f32 {
+        kernel.taps.iter().map(|&(_, _, weight)| weight).sum::<f32>() / kernel.divisor
+    }
+
+    #[test]
+    fn kernels_diffuse_the_whole_error() {
+        assert_eq!(diffused(&FLOYD_STEINBERG), 1.0);
+        assert_eq!(diffused(&JARVIS_JUDICE_NINKE), 1.0);
+        assert_eq!(diffused(&ATKINSON), 0.75);
+    }
+
+    #[test]
+    fn kernels_only_push_error_forward() {
+        for kernel in [&FLOYD_STEINBERG, &ATKINSON, &JARVIS_JUDICE_NINKE] {
+            assert!(kernel.taps.iter().all(|&(dx, dy, _)| dy > 0 || dx > 0));
+        }
+    }
+
+    #[test]
+    fn bayer_ranks_visit_every_threshold_once() {
+        for bits in [2, 3] {
+            let side = 1 << bits;
+            let mut ranks: Vec<usize> = (0..side * side).map(|i| bayer_rank(i % side, i / side, bits)).collect();
+            ranks.sort_unstable();
+            assert_eq!(ranks, (0..side * side).collect::<Vec<_>>());
+        }
+        // The classic 4x4 matrix's first row.
+        assert_eq!((0..4).map(|x| bayer_rank(x, 0, 2)).collect::<Vec<_>>(), [0, 8, 2, 10]);
+    }
+
+    #[test]
+    fn stable_diffusion_stays_inside_its_tile() {
+        // A lone mid-gray pixel at the end of the first tile row; its error must not reach the next tile.
+        let mut values = Array2::from_elem((1, 2 * STABLE_TILE), [0.0f32]);
+        values[[0, STABLE_TILE - 1]] = [100.0];
+        let quantize = |[v]: [f32; 1]| { let level = if v >= 128.0 { 255.0 } else { 0.0 }; (v, [level]) };
+        let stable = Dither::FloydSteinberg.apply(values.clone(), 255.0, true, quantize);
+        let spread = Dither::FloydSteinberg.apply(values, 255.0, false, quantize);
+        assert_eq!(stable[[0, STABLE_TILE]], 0.0);
+        assert!(spread[[0, STABLE_TILE]] > 0.0);
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Outline pass: cells on a strong gradient become a character drawn along the edge.
+
+use ndarray::{Array2, ArrayView2};
+
+/// Largest Sobel response along one axis for 0-255 input; thresholds are fractions of it.
+const MAX_MAGNITUDE: f32 = 4.0 * 255.0;
+
+/// Directional character per cell, or `None` where the gradient is weaker than `threshold` (0 to 1).
+/// `cells` holds one adjusted gray value per character cell; angles are measured on the character
+/// grid, which is how the result reads once printed.
+pub fn directions(cells: ArrayView2<f32>, threshold: f32, invert: bool) -> Array2<Option<u8>> {
+    let (h, w) = cells.dim();
+    let at = |y: usize, x: usize, dy: isize, dx: isize| {
+        let y = y.saturating_add_signed(dy).min(h - 1);
+        let x = x.saturating_add_signed(dx).min(w - 1);
+        cells[[y, x]]
+    };
+    Array2::from_shape_fn((h, w), |(y, x)| {
+        let gx = at(y, x, -1, 1) + 2.0 * at(y, x, 0, 1) + at(y, x, 1, 1)
+            - at(y, x, -1, -1) - 2.0 * at(y, x, 0, -1) - at(y, x, 1, -1);
+        let gy = at(y, x, 1, -1) + 2.0 * at(y, x, 1, 0) + at(y, x, 1, 1)
+            - at(y, x, -1, -1) - 2.0 * at(y, x, -1, 0) - at(y, x, -1, 1);
+        if gx.hypot(gy) / MAX_MAGNITUDE < threshold { return None; }
+        // The edge runs across the gradient; measure it counter-clockwise from horizontal with y up.
+        let angle = (-gx).atan2(-gy).to_degrees().rem_euclid(180.0);
+        Some(match angle {
+            // Ink sits low in `_`, so use it when the inked side of the edge is below.
+            a if !(22.5..157.5).contains(&a) => if (gy < 0.0) != invert { b'_' } else { b'-' },
+            a if a < 67.5 => b'/',
+            a if a < 112.5 => b'|',
+            _ => b'\\',
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Direction drawn at the centre of a 6x6 field split by `dark`.
+    fn centre(dark: impl Fn(usize, usize) -> bool, invert: bool) -> Option<u8> {
+        let cells = Array2::from_shape_fn((6, 6), |(y, x)| if dark(y, x) { 0.0 } else { 255.0 });
+        directions(cells.view(), 0.5, invert)[[2, 3]]
+    }
+
+    #[test]
+    fn flat_areas_have_no_edge() {
+        assert_eq!(centre(|_, _| false, false), None);
+    }
+
+    #[test]
+    fn vertical_edges_draw_a_bar() {
+        assert_eq!(centre(|_, x| x < 3, false), Some(b'|'));
+        assert_eq!(centre(|_, x| x >= 3, false), Some(b'|'));
+    }
+
+    #[test]
+    fn horizontal_edges_sit_on_the_inked_side() {
+        // Ink below the edge: the underscore hugs it from above.
+        assert_eq!(centre(|y, _| y >= 3, false), Some(b'_'));
+        assert_eq!(centre(|y, _| y < 3, false), Some(b'-'));
+        // Inverted, the light side is inked.
+        assert_eq!(centre(|y, _| y >= 3, true), Some(b'-'));
+        assert_eq!(centre(|y, _| y < 3, true), Some(b'_'));
+    }
+
+    #[test]
+    fn diagonals_lean_with_the_edge() {
+        assert_eq!(centre(|y, x| x + y < 5, false), Some(b'/'));
+        assert_eq!(centre(|y, x| x < y + 1, false), Some(b'\\'));
+    }
+}
@@ -0,0 +1,169 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Render a document straight from the tensor cache into a file, a batch of frames at a time,
+//! so an export never holds more than one batch of text.
+
+use crate::cast::CastSink;
+use crate::html::HtmlSink;
+use crate::media::{LoopCount, Timing};
+use crate::native::{Animation, NativeSink};
+use crate::render::{self, RenderSettings};
+use crate::svg::SvgSink;
+use ab_glyph::FontVec;
+use ndarray::Array3;
+use serde::Deserialize;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Frames rendered in parallel before they are written out.
+const BATCH_FRAMES: usize = 32;
+
+/// File formats `export` can write.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    /// Frames one after another under `--- FRAME n (ms) ---` separators.
+    #[default]
+    Text,
+    /// The versioned `.ascii` format that `native::read` opens again.
+    Native,
+    /// An asciinema v2 `.cast` recording.
+    Cast,
+    /// A single HTML page that plays the frames offline.
+    Html,
+    /// Vector text, animated with SMIL when there is more than one frame.
+    Svg,
+}
+
+/// Choices only some formats read.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportOptions {
+    /// Gzip an HTML page's frames; the page inflates them with the browser's `DecompressionStream`.
+    pub compress: bool,
+    /// CSS font family for SVG text; the preview's monospace stack when unset.
+    pub font_family: Option<String>,
+}
+
+/// A document's cached samples and what to render from them.
+pub struct Job<'a> {
+    pub tensor: &'a Array3<u8>,
+    /// The matching RGB tensor when `settings.color` is on.
+    pub colors: Option<&'a Array3<u8>>,
+    pub settings: &'a RenderSettings,
+    /// Only needed for `RenderMode::Shape`.
+    pub font: Option<&'a FontVec>,
+    pub timing: &'a Timing,
+    /// File name the document was loaded from.
+    pub source: &'a str,
+}
+
+/// What a format may record besides the frames themselves.
+pub struct Header<'a> {
+    pub columns: usize,
+    pub rows: usize,
+    pub frame_count: usize,
+    pub timing: &'a Timing,
+    pub source: &'a str,
+    pub settings: &'a RenderSettings,
+}
+
+/// Receives rendered frames in order; each frame is its rows, every one ending in `\n`.
+pub trait FrameSink {
+    fn frame(&mut self, index: usize, delay_ms: u32, text: &[u8]) -> io::Result<()>;
+
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+/// Render every frame of `job` and write it to `out` as `format`.
+pub fn export<'w>(job: &Job, format: ExportFormat, options: &ExportOptions, out: impl Write + 'w) -> Result<(), String> {
+    let grid = job.settings.mode.grid();
+    let (frame_count, sample_rows, sample_cols) = job.tensor.dim();
+    let header = Header {
+        columns: sample_cols / grid.cols as usize,
+        rows: sample_rows / grid.rows as usize,
+        frame_count,
+        timing: job.timing,
+        source: job.source,
+        settings: job.settings,
+    };
+    let mut sink = open_sink(&header, format, options, out).map_err(|e| e.to_string())?;
+    let frames: Vec<usize> = (0..frame_count).collect();
+    for batch in frames.chunks(BATCH_FRAMES) {
+        let rendered = render::render(job.tensor, job.colors, batch, job.settings, job.font)?;
+        for (i, &index) in batch.iter().enumerate() {
+            let text = &rendered.data[rendered.offsets[i]..rendered.offsets[i + 1]];
+            sink.frame(index, job.timing.delay_ms(index), text).map_err(|e| e.to_string())?;
+        }
+    }
+    sink.finish().map_err(|e| e.to_string())
+}
+
+/// Write an animation that was saved earlier as `format`, frames as they were rendered then.
+pub fn reexport<'w>(
+    animation: &Animation,
+    format: ExportFormat,
+    options: &ExportOptions,
+    out: impl Write + 'w
+) -> Result<(), String> {
+    let mut sink = open_sink(&animation.header(), format, options, out).map_err(|e| e.to_string())?;
+    for index in 0..animation.frame_count {
+        sink.frame(index, animation.timing.delay_ms(index), animation.frame(index)).map_err(|e| e.to_string())?;
+    }
+    sink.finish().map_err(|e| e.to_string())
+}
+
+/// Write to a temporary file beside `path` and move it over `path` only once `write` succeeds,
+/// so a failed export leaves an existing file untouched.
+pub fn to_file(path: &str, write: impl FnOnce(BufWriter<File>) -> Result<(), String>) -> Result<(), String> {
+    let path = Path::new(path);
+    let name = path.file_name().ok_or("Export path has no file name")?;
+    let partial = path.with_file_name(format!(".{}.partial", name.to_string_lossy()));
+    let result = File::create(&partial).map_err(|e| e.to_string())
+        .and_then(|file| write(BufWriter::new(file)))
+        .and_then(|()| fs::rename(&partial, path).map_err(|e| e.to_string()));
+    if result.is_err() { let _ = fs::remove_file(&partial); }
+    result
+}
+
+fn open_sink<'w>(
+    header: &Header,
+    format: ExportFormat,
+    options: &ExportOptions,
+    out: impl Write + 'w
+) -> io::Result<Box<dyn FrameSink + 'w>> {
+    Ok(match format {
+        ExportFormat::Text => Box::new(TextSink::open(header, out)?),
+        ExportFormat::Native => Box::new(NativeSink::open(header, out)?),
+        ExportFormat::Cast => Box::new(CastSink::open(header, out)?),
+        ExportFormat::Html => Box::new(HtmlSink::open(header, options, out)?),
+        ExportFormat::Svg => Box::new(SvgSink::open(header, options, out)?),
+    })
+}
+
+struct TextSink<W: Write> {
+    out: W,
+}
+
+impl<W: Write> TextSink<W> {
+    fn open(header: &Header, mut out: W) -> io::Result<Self> {
+        match header.timing.loop_count {
+            LoopCount::Forever => writeln!(out, "--- LOOP FOREVER ---")?,
+            LoopCount::Times(n) => writeln!(out, "--- LOOP {} ---", n)?,
+        }
+        Ok(Self { out })
+    }
+}
+
+impl<W: Write> FrameSink for TextSink<W> {
+    fn frame(&mut self, index: usize, delay_ms: u32, text: &[u8]) -> io::Result<()> {
+        writeln!(self.out, "--- FRAME {} ({}ms) ---", index, delay_ms)?;
+        self.out.write_all(text)?;
+        writeln!(self.out)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Read monospace font metrics and glyph ink so output matches the font it is shown in.
+
+use ab_glyph::{point, Font, FontVec, PxScale, ScaleFont};
+
+/// Pixel height glyphs are rasterized at when measuring ink; large enough that hinting noise washes out.
+const MEASURE_PX: f32 = 64.0;
+
+pub fn load(path: &str) -> Result<FontVec, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    FontVec::try_from_vec(bytes).map_err(|_| format!("{} is not a TrueType or OpenType font", path))
+}
+
+/// Width over height of one character cell: the advance of `M` against the line height,
+/// scaled by the extra `line_spacing` a terminal or stylesheet adds between rows.
+pub fn cell_aspect(font: &FontVec, line_spacing: f32) -> Result<f32, String> {
+    let advance = font.h_advance_unscaled(font.glyph_id('M'));
+    let line_height = (font.ascent_unscaled() - font.descent_unscaled() + font.line_gap_unscaled()) * line_spacing;
+    if advance <= 0.0 || line_height <= 0.0 { return Err("Font has no usable metrics".into()); }
+    Ok(advance / line_height)
+}
+
+/// Share of the character cell each glyph inks, from 0 (blank) to 1 (solid). `None` where the font lacks the glyph.
+pub fn coverage(font: &FontVec, glyphs: &[char]) -> Vec<Option<f32>> {
+    glyphs.iter().map(|&c| rasterize(font, c, 1, 1).map(|ink| ink[0])).collect()
+}
+
+/// Ink of `c` in each cell of a `cols` x `rows` grid laid over one character cell, row-major.
+pub fn rasterize(font: &FontVec, c: char, cols: usize, rows: usize) -> Option<Vec<f32>> {
+    let id = font.glyph_id(c);
+    if id.0 == 0 { return None; }
+    let scale = PxScale::from(MEASURE_PX);
+    let scaled = font.as_scaled(scale);
+    let cell_w = scaled.h_advance(font.glyph_id('M'));
+    let cell_h = scaled.ascent() - scaled.descent();
+    let mut ink = vec![0.0; cols * rows];
+    if let Some(outlined) = font.outline_glyph(id.with_scale_and_position(scale, point(0.0, scaled.ascent()))) {
+        let bounds = outlined.px_bounds();
+        outlined.draw(|x, y, alpha| {
+            let col = ((bounds.min.x + x as f32 + 0.5) / cell_w * cols as f32).floor();
+            let row = ((bounds.min.y + y as f32 + 0.5) / cell_h * rows as f32).floor();
+            if (0.0..cols as f32).contains(&col) && (0.0..rows as f32).contains(&row) {
+                ink[row as usize * cols + col as usize] += alpha;
+            }
+        });
+    }
+    let sub_area = cell_w * cell_h / (cols * rows) as f32;
+    Some(ink.into_iter().map(|v| (v / sub_area).min(1.0)).collect())
+}
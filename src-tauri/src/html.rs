@@ -0,0 +1,229 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Write a self-contained HTML page that plays an animation: every frame as `<pre>` markup with a
+//! `<span>` per run of equal colors, the real frame delays, and a small inline player. Nothing is
+//! fetched, so the page works offline.
+
+use crate::ansi::{self, Rgb, Style};
+use crate::export::{ExportOptions, FrameSink, Header};
+use crate::media::LoopCount;
+use base64::{Engine as _, engine::general_purpose};
+use flate2::{write::GzEncoder, Compression};
+use std::io::{self, Write};
+
+/// Matches the app's preview, so colorless frames look the way they did there.
+const PAGE_START: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+body { margin: 0; min-height: 100vh; display: flex; flex-direction: column; align-items: center; justify-content: center; gap: 12px; background: #000; color: #b8b1b1; font-family: sans-serif; }
+#screen { margin: 0; font-family: "Fira Code", Consolas, monospace; font-size: 8px; line-height: 1; }
+.controls { display: flex; align-items: center; gap: 16px; font-size: 13px; }
+button, select { background: #111; color: #b8b1b1; border: 1px solid #333; padding: 4px 10px; font: inherit; }
+</style>
+</head>
+<body>
+<pre id="screen"></pre>
+<div class="controls">
+<button id="play" type="button">Play</button>
+<label><input id="loop" type="checkbox"> Loop</label>
+<label>Speed <select id="speed"><option value="0.25">0.25x</option><option value="0.5">0.5x</option><option value="1" selected>1x</option><option value="2">2x</option><option value="4">4x</option></select></label>
+</div>
+<script>
+"#;
+
+/// Plays `FRAMES`, or the gzipped JSON array in `PACKED`, holding frame `i` for `DELAYS[i]`
+/// milliseconds. `PLAYS` is how many times to play through unless Loop is ticked; 0 is forever.
+const PLAYER: &str = r#"
+const screen = document.getElementById("screen");
+const playButton = document.getElementById("play");
+const loopBox = document.getElementById("loop");
+const speedSelect = document.getElementById("speed");
+let frames = [];
+let index = 0;
+let plays = 1;
+let timer = 0;
+
+const show = (i) => {
+  index = i;
+  screen.innerHTML = frames[i];
+};
+
+const setPlaying = (playing) => {
+  clearTimeout(timer);
+  playButton.textContent = playing ? "Pause" : "Play";
+  if (playing) schedule();
+  else timer = 0;
+};
+
+const schedule = () => {
+  timer = setTimeout(() => {
+    if (index + 1 < frames.length) {
+      show(index + 1);
+    } else if (loopBox.checked || plays < PLAYS) {
+      plays += 1;
+      show(0);
+    } else {
+      setPlaying(false);
+      return;
+    }
+    schedule();
+  }, DELAYS[index] / Number(speedSelect.value));
+};
+
+playButton.addEventListener("click", () => {
+  if (timer) {
+    setPlaying(false);
+    return;
+  }
+  if (index + 1 >= frames.length) {
+    plays = 1;
+    show(0);
+  }
+  setPlaying(true);
+});
+
+const inflate = async (packed) => {
+  const bytes = Uint8Array.from(atob(packed), (c) => c.charCodeAt(0));
+  const stream = new Blob([bytes]).stream().pipeThrough(new DecompressionStream("gzip"));
+  return JSON.parse(await new Response(stream).text());
+};
+
+(async () => {
+  loopBox.checked = PLAYS === 0;
+  frames = PACKED ? await inflate(PACKED) : FRAMES;
+  if (frames.length === 0) return;
+  show(0);
+  setPlaying(frames.length > 1);
+})();
+</script>
+</body>
+</html>
+"#;
+
+pub struct HtmlSink<W: Write> {
+    out: W,
+    /// The JSON array of frames while it is being compressed; `finish` writes it out.
+    packed: Option<GzEncoder<Vec<u8>>>,
+}
+
+impl<W: Write> HtmlSink<W> {
+    pub fn open(header: &Header, options: &ExportOptions, mut out: W) -> io::Result<Self> {
+        out.write_all(PAGE_START.replace("{title}", &escape(header.source)).as_bytes())?;
+        let delays: Vec<u32> = (0..header.frame_count).map(|i| header.timing.delay_ms(i)).collect();
+        writeln!(out, "const DELAYS = {};", serde_json::to_string(&delays)?)?;
+        let plays = match header.timing.loop_count {
+            LoopCount::Forever => 0,
+            LoopCount::Times(n) => n.max(1),
+        };
+        writeln!(out, "const PLAYS = {};", plays)?;
+        let packed = if options.compress {
+            let mut packed = GzEncoder::new(Vec::new(), Compression::best());
+            packed.write_all(b"[")?;
+            Some(packed)
+        } else {
+            writeln!(out, "const FRAMES = [")?;
+            None
+        };
+        Ok(Self { out, packed })
+    }
+}
+
+impl<W: Write> FrameSink for HtmlSink<W> {
+    fn frame(&mut self, index: usize, _delay_ms: u32, text: &[u8]) -> io::Result<()> {
+        let frame = serde_json::to_string(&frame_html(text))?;
+        match &mut self.packed {
+            Some(packed) => {
+                if index > 0 { packed.write_all(b",")?; }
+                packed.write_all(frame.as_bytes())
+            }
+            None => writeln!(self.out, "{},", frame),
+        }
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        match self.packed.take() {
+            Some(mut packed) => {
+                packed.write_all(b"]")?;
+                let packed = general_purpose::STANDARD.encode(packed.finish()?);
+                writeln!(self.out, "const FRAMES = [];")?;
+                writeln!(self.out, "const PACKED = \"{}\";", packed)?;
+            }
+            None => {
+                writeln!(self.out, "];")?;
+                writeln!(self.out, "const PACKED = null;")?;
+            }
+        }
+        self.out.write_all(PLAYER.as_bytes())?;
+        self.out.flush()
+    }
+}
+
+/// A frame's rows as markup for the `<pre>`, uncolored runs as bare text.
+fn frame_html(text: &[u8]) -> String {
+    let mut html = String::with_capacity(text.len());
+    for runs in ansi::styled_rows(&String::from_utf8_lossy(text)) {
+        for run in runs {
+            match css(run.style) {
+                Some(css) => html.push_str(&format!("<span style=\"{}\">{}</span>", css, escape(&run.text))),
+                None => html.push_str(&escape(&run.text)),
+            }
+        }
+        html.push('\n');
+    }
+    html
+}
+
+fn css(style: Style) -> Option<String> {
+    match (style.fg, style.bg) {
+        (None, None) => None,
+        (Some(fg), None) => Some(format!("color:{}", hex(fg))),
+        (None, Some(bg)) => Some(format!("background:{}", hex(bg))),
+        (Some(fg), Some(bg)) => Some(format!("color:{};background:{}", hex(fg), hex(bg))),
+    }
+}
+
+pub fn hex([r, g, b]: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Escape text for markup, quotes included so it can go in attributes too.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media::Timing;
+    use crate::render::RenderSettings;
+
+    #[test]
+    fn escapes_markup_in_frames() {
+        assert_eq!(frame_html(b"<a&b>\n\"\n"), "&lt;a&amp;b&gt;\n&quot;\n");
+        assert_eq!(
+            frame_html(b"\x1b[38;2;255;0;0m<\x1b[0m&\n"),
+            "<span style=\"color:#ff0000\">&lt;</span>&amp;\n"
+        );
+    }
+
+    #[test]
+    fn page_never_carries_raw_frame_markup() {
+        let timing = Timing { delays_ms: vec![50], loop_count: LoopCount::Times(2) };
+        let settings = RenderSettings::default();
+        let header = Header { columns: 9, rows: 1, frame_count: 1, timing: &timing, source: "<clip>.gif", settings: &settings };
+        let mut out = Vec::new();
+        let mut sink = HtmlSink::open(&header, &ExportOptions::default(), &mut out).unwrap();
+        sink.frame(0, 50, b"</script>\n").unwrap();
+        sink.finish().unwrap();
+
+        let page = String::from_utf8(out).unwrap();
+        assert!(page.contains("<title>&lt;clip&gt;.gif</title>"));
+        assert!(page.contains("\"&lt;/script&gt;\\n\","));
+        assert_eq!(page.matches("</script>").count(), 1);
+        assert!(page.contains("const DELAYS = [50];") && page.contains("const PLAYS = 2;"));
+    }
+}
@@ -1,167 +1,326 @@
 // Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
 
-//! Perform hyper-performance GIF to ASCII conversion using a pre-calculated Tensor Cache.
+//! Perform hyper-performance GIF to ASCII conversion using an on-demand Tensor Cache.
 
-use image::AnimationDecoder;
-use image::codecs::gif::GifDecoder;
+mod ansi;
+mod cache;
+mod cast;
+mod dither;
+mod edges;
+mod export;
+mod font;
+mod html;
+mod media;
+mod native;
+mod palette;
+mod progress;
+mod ramp;
+mod render;
+mod shape;
+mod svg;
+mod y4m;
+
+use ab_glyph::FontVec;
+use cache::{CacheConfig, CacheKey, CellAspect, Resample, SubCells, WidthCache};
+use export::{ExportFormat, ExportOptions, Job};
 use image::RgbaImage;
-use ndarray::{Array3, s};
+use media::{DecodedMedia, Timing};
+use native::Animation;
+use ndarray::s;
+use progress::{LoadMonitor, LoadProgress, LoadToken};
+use ramp::MeasuredRamp;
+use render::RenderSettings;
+use serde::Serialize;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufReader, Write};
-use std::sync::RwLock;
-use rayon::prelude::*;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use tauri::ipc::Channel;
 use tauri::State;
 
-const ASCII_CHARS: &[u8] = b"$$@B%8&WM#*oahkbdpqwmZO0QLCJUYXzcvunxrjft/\\|()1{}[]?-_+~<>i!lI;:,\"^`'. ";
+/// Source preview is rendered from this cached width.
+const PREVIEW_WIDTH: u32 = cache::MAX_WIDTH;
+
+/// Ramp length `build_ramp` aims for when the caller does not ask for one.
+const DEFAULT_MEASURED_LEVELS: usize = 32;
+
+/// Returned when a request names a handle that has been closed or replaced; the frontend drops the response.
+const STALE_MEDIA: &str = "Stale media";
+
+/// Everything derived from one loaded file. Built in full before it is published; only the
+/// per-document display settings change afterwards.
+struct Document {
+    cache: Arc<WidthCache>,
+    timing: Timing,
+    cell_aspect: RwLock<CellAspect>,
+    /// File name the frames came from, recorded in exports.
+    source: String,
+}
+
+impl Document {
+    fn key(&self, width: u32, filter: Resample, grid: SubCells) -> Result<CacheKey, String> {
+        let cell_aspect = *self.cell_aspect.read().map_err(|_| "Lock failed")?;
+        Ok(CacheKey { width, filter, cell_aspect, grid, rgb: false })
+    }
+}
 
+/// Open documents keyed by the opaque `media_id` handle that loading returns.
+#[derive(Default)]
 pub struct AppState {
-    width_cache: RwLock<HashMap<u32, Array3<u8>>>,
-    frame_count: RwLock<usize>,
+    documents: RwLock<HashMap<u64, Arc<Document>>>,
+    next_media_id: AtomicU64,
+    cache_config: RwLock<CacheConfig>,
+    active_load: Mutex<Option<LoadToken>>,
+    /// Font whose glyph shapes `RenderMode::Shape` matches against.
+    glyph_font: RwLock<Option<Arc<FontVec>>>,
 }
 
-impl Default for AppState {
-    fn default() -> Self {
-        Self {
-            width_cache: RwLock::new(HashMap::new()),
-            frame_count: RwLock::new(0),
+impl AppState {
+    fn document(&self, media_id: u64) -> Result<Arc<Document>, String> {
+        self.documents.read().map_err(|_| "Lock failed")?.get(&media_id).cloned().ok_or_else(|| STALE_MEDIA.into())
+    }
+
+    /// Cancel whatever load is still running and register a token for the new one.
+    fn begin_load(&self) -> Result<LoadToken, String> {
+        let mut active = self.active_load.lock().map_err(|_| "Lock failed")?;
+        if let Some(previous) = active.take() { previous.cancel(); }
+        let token = LoadToken::default();
+        *active = Some(token.clone());
+        Ok(token)
+    }
+
+    fn end_load(&self, token: &LoadToken) {
+        if let Ok(mut active) = self.active_load.lock() {
+            if active.as_ref().is_some_and(|current| current.same_as(token)) { *active = None; }
         }
     }
 }
 
+/// What the frontend needs to drive playback of a freshly loaded source.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MediaInfo {
+    media_id: u64,
+    frame_count: usize,
+    #[serde(flatten)]
+    timing: Timing,
+}
+
+#[tauri::command]
+async fn load_gif(
+    state: State<'_, AppState>,
+    path: String,
+    width: u32,
+    cell_aspect: f32,
+    on_progress: Channel<LoadProgress>
+) -> Result<MediaInfo, String> {
+    run_load(&state, &path, width, cell_aspect, on_progress, |monitor| media::decode_gif(&path, monitor))
+}
+
+/// Load any supported still or animated image, detecting the format from the file contents.
 #[tauri::command]
-async fn load_gif(state: State<'_, AppState>, path: String) -> Result<usize, String> {
-    let file = File::open(&path).map_err(|e| e.to_string())?;
-    let reader = BufReader::new(file);
-    let decoder = GifDecoder::new(reader).map_err(|e| e.to_string())?;
-    let frames = decoder.into_frames().collect_frames().map_err(|e| e.to_string())?;
+async fn load_media(
+    state: State<'_, AppState>,
+    path: String,
+    width: u32,
+    cell_aspect: f32,
+    on_progress: Channel<LoadProgress>
+) -> Result<MediaInfo, String> {
+    run_load(&state, &path, width, cell_aspect, on_progress, |monitor| media::decode(&path, monitor))
+}
 
-    if frames.is_empty() { return Ok(0); }
+/// Load a numbered image sequence (a directory or glob pattern) as an animation played at `fps`.
+#[tauri::command]
+async fn load_sequence(
+    state: State<'_, AppState>,
+    pattern: String,
+    fps: f32,
+    letterbox: bool,
+    width: u32,
+    cell_aspect: f32,
+    on_progress: Channel<LoadProgress>
+) -> Result<MediaInfo, String> {
+    run_load(&state, &pattern, width, cell_aspect, on_progress, |monitor| media::decode_sequence(&pattern, fps, letterbox, monitor))
+}
+
+/// Abort the running load; it resolves with `progress::CANCELLED`.
+#[tauri::command]
+async fn cancel_load(state: State<'_, AppState>) -> Result<(), String> {
+    if let Some(token) = state.active_load.lock().map_err(|_| "Lock failed")?.take() { token.cancel(); }
+    Ok(())
+}
+
+/// Decode, then warm the width and cell shape the frontend shows first plus the preview, so the first frame appears at once.
+fn run_load(
+    state: &AppState,
+    path: &str,
+    width: u32,
+    cell_aspect: f32,
+    on_progress: Channel<LoadProgress>,
+    decode: impl FnOnce(&LoadMonitor) -> Result<DecodedMedia, String>
+) -> Result<MediaInfo, String> {
+    let cell_aspect = CellAspect::new(cell_aspect)?;
+    let token = state.begin_load()?;
+    let monitor = LoadMonitor::new(token.clone(), move |progress| { let _ = on_progress.send(progress); });
+    let source = Path::new(path).file_name().map_or_else(|| path.to_string(), |name| name.to_string_lossy().into_owned());
+    let result = decode(&monitor).and_then(|decoded| install_media(state, decoded, source, width, cell_aspect, &monitor));
+    state.end_load(&token);
+    result
+}
+
+fn install_media(
+    state: &AppState,
+    decoded: DecodedMedia,
+    source: String,
+    width: u32,
+    cell_aspect: CellAspect,
+    monitor: &LoadMonitor
+) -> Result<MediaInfo, String> {
+    let DecodedMedia { frames, timing } = decoded;
+    if frames.is_empty() { return Err("Media has no frames".into()); }
 
     let frame_count = frames.len();
-    let rgba_frames: Vec<RgbaImage> = frames.into_iter().map(|f| f.into_buffer()).collect();
-    let (orig_w, orig_h) = (rgba_frames[0].width(), rgba_frames[0].height());
-    let aspect_ratio = orig_h as f32 / orig_w as f32;
-
-    {
-        let mut cache = state.width_cache.write().map_err(|_| "Lock failed")?;
-        cache.clear();
-        *state.frame_count.write().map_err(|_| "Lock failed")? = frame_count;
-    }
+    let config = *state.cache_config.read().map_err(|_| "Lock failed")?;
+    let cache = Arc::new(WidthCache::new(frames, config));
+    let preview = CacheKey {
+        width: PREVIEW_WIDTH,
+        filter: Resample::default(),
+        cell_aspect: CellAspect::DEFAULT,
+        grid: SubCells::ONE,
+        rgb: false,
+    };
+    let first = CacheKey { width, cell_aspect, ..preview };
+    cache.warm(&if first == preview { vec![first] } else { vec![first, preview] }, monitor)?;
 
-    let widths: Vec<u32> = (20..=250).collect();
-    let results: Vec<(u32, Array3<u8>)> = widths.par_iter().map(|&w| {
-        let h = (w as f32 * aspect_ratio * 0.5) as u32;
-        let h = h.max(1);
-        let mut tensor = Array3::<u8>::zeros((frame_count, h as usize, w as usize));
-        for (f_idx, rgba) in rgba_frames.iter().enumerate() {
-            let pixels = rgba.as_raw();
-            for y in 0..h {
-                let src_y = (y * orig_h / h) * orig_w * 4;
-                for x in 0..w {
-                    let src_x = (x * orig_w / w) * 4;
-                    let offset = (src_y + src_x) as usize;
-                    let r = pixels[offset];
-                    let g = pixels[offset + 1];
-                    let b = pixels[offset + 2];
-                    let a = pixels[offset + 3];
-                    let val = if a < 128 { 255 } else {
-                        ((r as u32 * 19595 + g as u32 * 38470 + b as u32 * 7471) >> 16) as u8
-                    };
-                    tensor[[f_idx, y as usize, x as usize]] = val;
-                }
-            }
-        }
-        (w, tensor)
-    }).collect();
+    let media_id = state.next_media_id.fetch_add(1, Ordering::Relaxed) + 1;
+    let document = Arc::new(Document {
+        cache,
+        timing: timing.clone(),
+        cell_aspect: RwLock::new(cell_aspect),
+        source,
+    });
+    state.documents.write().map_err(|_| "Lock failed")?.insert(media_id, document);
+    Ok(MediaInfo { media_id, frame_count, timing })
+}
 
-    let mut cache = state.width_cache.write().map_err(|_| "Lock failed")?;
-    for (w, tensor) in results { cache.insert(w, tensor); }
-    Ok(frame_count)
+/// Release a document's frames and tensors; requests still in flight for it fail as stale.
+#[tauri::command]
+async fn close_media(state: State<'_, AppState>, media_id: u64) -> Result<(), String> {
+    state.documents.write().map_err(|_| "Lock failed")?.remove(&media_id);
+    Ok(())
+}
+
+/// Set the width-over-height shape of the character cells a document's output will be shown in.
+/// Returns the ratio actually applied.
+#[tauri::command]
+async fn set_cell_aspect(state: State<'_, AppState>, media_id: u64, aspect: f32) -> Result<f32, String> {
+    let aspect = CellAspect::new(aspect)?;
+    *state.document(media_id)?.cell_aspect.write().map_err(|_| "Lock failed")? = aspect;
+    Ok(aspect.ratio())
+}
+
+/// Match a document's cell shape to a monospace font file, optionally with extra line spacing.
+#[tauri::command]
+async fn set_cell_aspect_from_font(
+    state: State<'_, AppState>,
+    media_id: u64,
+    font_path: String,
+    line_spacing: Option<f32>
+) -> Result<f32, String> {
+    let font = font::load(&font_path)?;
+    let ratio = font::cell_aspect(&font, line_spacing.unwrap_or(1.0))?;
+    set_cell_aspect(state, media_id, ratio).await
+}
+
+/// Measure how much ink each candidate character leaves in a font and return an evenly spaced,
+/// density-sorted ramp of up to `levels` characters for `RampSpec::Measured`.
+#[tauri::command]
+async fn build_ramp(font_path: String, candidates: Option<String>, levels: Option<usize>) -> Result<MeasuredRamp, String> {
+    let font = font::load(&font_path)?;
+    ramp::measure(&font, candidates.as_deref(), levels.unwrap_or(DEFAULT_MEASURED_LEVELS))
+}
+
+/// Load the font shape matching draws its candidate glyphs with.
+#[tauri::command]
+async fn set_glyph_font(state: State<'_, AppState>, font_path: String) -> Result<(), String> {
+    let font = font::load(&font_path)?;
+    *state.glyph_font.write().map_err(|_| "Lock failed")? = Some(Arc::new(font));
+    Ok(())
+}
+
+/// Set the tensor cache memory budget and whether neighbouring widths are built ahead of time.
+#[tauri::command]
+async fn configure_cache(state: State<'_, AppState>, config: CacheConfig) -> Result<(), String> {
+    *state.cache_config.write().map_err(|_| "Lock failed")? = config;
+    for document in state.documents.read().map_err(|_| "Lock failed")?.values() { document.cache.configure(config)?; }
+    Ok(())
+}
+
+/// UTF-8 text for the requested frames; frame `i` is `data[offsets[i]..offsets[i + 1]]`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AsciiFrames {
+    media_id: u64,
+    height: u32,
+    data: Vec<u8>,
+    offsets: Vec<usize>,
 }
 
-/// Optimized conversion returning (Height, Data) for zero-measure scaling.
+/// Optimized conversion returning the row count with the data for zero-measure scaling.
 #[tauri::command]
 async fn convert_gif_to_ascii(
     state: State<'_, AppState>,
-    width: u32,
-    brightness: i32,
-    contrast: f32,
+    media_id: u64,
+    settings: RenderSettings,
     only_frame: Option<usize>
-) -> Result<(u32, Vec<u8>), String> {
-    let cache = state.width_cache.read().map_err(|_| "Lock failed")?;
-    let tensor = cache.get(&width).ok_or("Width not cached")?;
-    let (frame_count, height, w_usize) = tensor.dim();
-    let height_u32 = height as u32;
-
-    let mut lut = [0u8; 256];
-    let ascii_len = (ASCII_CHARS.len() - 1) as f32;
-    for i in 0..256 {
-        let mut val = i as f32 + brightness as f32;
-        if (contrast - 1.0).abs() > 0.01 { val = (val - 128.0) * contrast + 128.0; }
-        let char_index = (val.clamp(0.0, 255.0) as f32 * ascii_len / 255.0) as usize;
-        lut[i] = ASCII_CHARS[char_index];
-    }
+) -> Result<AsciiFrames, String> {
+    let document = state.document(media_id)?;
+    let grid = settings.mode.grid();
+    let key = document.key(settings.width, settings.filter, grid)?;
+    let tensor = document.cache.get(key)?;
+    document.cache.prewarm(key);
+    let (frame_count, sample_rows, _) = tensor.dim();
+    let frames: Vec<usize> = match only_frame {
+        Some(target_idx) => vec![target_idx % frame_count],
+        None => (0..frame_count).collect(),
+    };
+    let font = state.glyph_font.read().map_err(|_| "Lock failed")?.clone();
+    let colors = if settings.color { Some(document.cache.get(CacheKey { rgb: true, ..key })?) } else { None };
+    let rendered = render::render(&tensor, colors.as_deref(), &frames, &settings, font.as_deref())?;
+    let height = (sample_rows as u32) / grid.rows;
+    Ok(AsciiFrames { media_id, height, data: rendered.data, offsets: rendered.offsets })
+}
 
-    let frame_size = (w_usize * height + height) as usize;
-
-    if let Some(target_idx) = only_frame {
-        let mut output = vec![0u8; frame_size];
-        let mut write_ptr = 0;
-        let f_idx = target_idx % frame_count;
-        for y in 0..height {
-            for x in 0..w_usize {
-                unsafe {
-                    let gray = *tensor.uget((f_idx, y, x));
-                    *output.get_unchecked_mut(write_ptr) = *lut.get_unchecked(gray as usize);
-                }
-                write_ptr += 1;
-            }
-            output[write_ptr] = b'\n';
-            write_ptr += 1;
-        }
-        Ok((height_u32, output))
-    } else {
-        let mut output = vec![0u8; frame_size * frame_count];
-        output.par_chunks_exact_mut(frame_size).enumerate().for_each(|(f_idx, out_frame)| {
-            let mut write_ptr = 0;
-            for y in 0..height {
-                for x in 0..w_usize {
-                    unsafe {
-                        let gray = *tensor.uget((f_idx, y, x));
-                        *out_frame.get_unchecked_mut(write_ptr) = *lut.get_unchecked(gray as usize);
-                    }
-                    write_ptr += 1;
-                }
-                out_frame[write_ptr] = b'\n';
-                write_ptr += 1;
-            }
-        });
-        Ok((height_u32, output))
-    }
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Preview {
+    media_id: u64,
+    url: String,
 }
 
 #[tauri::command]
 async fn apply_adjustments_to_preview(
     state: State<'_, AppState>,
+    media_id: u64,
     brightness: i32,
     contrast: f32,
     frame_index: usize
-) -> Result<String, String> {
-    let cache = state.width_cache.read().map_err(|_| "Lock failed")?;
-    let tensor = cache.get(&250).or_else(|| cache.values().next()).ok_or("No media loaded")?;
-    let frame_count = *state.frame_count.read().map_err(|_| "Lock failed")?;
-    if frame_count == 0 { return Err("Empty".into()); }
+) -> Result<Preview, String> {
+    let tensor = state.document(media_id)?.cache.get(CacheKey {
+        width: PREVIEW_WIDTH,
+        filter: Resample::default(),
+        cell_aspect: CellAspect::DEFAULT,
+        grid: SubCells::ONE,
+        rgb: false,
+    })?;
+    let frame_count = tensor.dim().0;
     let f_idx = frame_index % frame_count;
     let frame = tensor.slice(s![f_idx, .., ..]);
     let (h, w) = frame.dim();
     let mut rgba_image = RgbaImage::new(w as u32, h as u32);
     for y in 0..h {
         for x in 0..w {
-            let gray = frame[[y, x]];
-            let mut val = gray as f32 + brightness as f32;
-            if (contrast - 1.0).abs() > 0.01 { val = (val - 128.0) * contrast + 128.0; }
-            let g_out = val.clamp(0.0, 255.0) as u8;
+            let g_out = render::adjust(frame[[y, x]], brightness, contrast).clamp(0.0, 255.0) as u8;
             rgba_image.put_pixel(x as u32, y as u32, image::Rgba([g_out, g_out, g_out, 255]));
         }
     }
@@ -169,20 +328,51 @@ async fn apply_adjustments_to_preview(
     let mut cursor = std::io::Cursor::new(&mut buffer);
     rgba_image.write_to(&mut cursor, image::ImageFormat::Png).map_err(|e| e.to_string())?;
     use base64::{Engine as _, engine::general_purpose};
-    Ok(format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(buffer)))
+    Ok(Preview { media_id, url: format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(buffer)) })
 }
 
+/// Render every frame with `settings` and write it to `path` as `format`, without passing frames through the frontend.
 #[tauri::command]
-async fn save_ascii_to_file(path: String, frames: Vec<String>) -> Result<(), String> {
-    let file = File::create(path).map_err(|e| e.to_string())?;
-    let mut writer = std::io::BufWriter::new(file);
-    for (i, frame) in frames.iter().enumerate() {
-        writeln!(writer, "--- FRAME {} ---", i).map_err(|e| e.to_string())?;
-        writer.write_all(frame.as_bytes()).map_err(|e| e.to_string())?;
-        writeln!(writer).map_err(|e| e.to_string())?;
-    }
-    writer.flush().map_err(|e| e.to_string())?;
-    Ok(())
+async fn export(
+    state: State<'_, AppState>,
+    media_id: u64,
+    settings: RenderSettings,
+    format: ExportFormat,
+    options: ExportOptions,
+    path: String
+) -> Result<(), String> {
+    let document = state.document(media_id)?;
+    let key = document.key(settings.width, settings.filter, settings.mode.grid())?;
+    let tensor = document.cache.get(key)?;
+    let colors = if settings.color { Some(document.cache.get(CacheKey { rgb: true, ..key })?) } else { None };
+    let font = state.glyph_font.read().map_err(|_| "Lock failed")?.clone();
+    let job = Job {
+        tensor: &tensor,
+        colors: colors.as_deref(),
+        settings: &settings,
+        font: font.as_deref(),
+        timing: &document.timing,
+        source: &document.source,
+    };
+    export::to_file(&path, |out| export::export(&job, format, &options, out))
+}
+
+/// Read a saved `.ascii` animation for playback; its frames are kept exactly as they were rendered.
+#[tauri::command]
+async fn open_animation(path: String) -> Result<Animation, String> {
+    native::read(&path)
+}
+
+/// Write the saved animation at `source` to `path` as `format`.
+#[tauri::command]
+async fn export_animation(
+    source: String,
+    format: ExportFormat,
+    options: ExportOptions,
+    path: String
+) -> Result<(), String> {
+    let animation = native::read(&source)?;
+    export::to_file(&path, |out| export::reexport(&animation, format, &options, out))
 }
 
 pub fn run() {
@@ -193,8 +383,19 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .invoke_handler(tauri::generate_handler![
             load_gif,
+            load_media,
+            load_sequence,
+            cancel_load,
+            close_media,
+            set_cell_aspect,
+            set_cell_aspect_from_font,
+            build_ramp,
+            set_glyph_font,
+            configure_cache,
             convert_gif_to_ascii,
-            save_ascii_to_file,
+            export,
+            open_animation,
+            export_animation,
             apply_adjustments_to_preview
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,333 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Decode source media into the planes the tensor cache samples from, along with the timing needed to play them back.
+
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::imageops::{self, FilterType};
+use image::{AnimationDecoder, DynamicImage, Frame, Frames, GrayImage, ImageFormat, ImageReader, RgbImage, RgbaImage};
+use crate::progress::{LoadMonitor, Tracked};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+/// Fallback for frames that carry no usable delay.
+pub const DEFAULT_DELAY_MS: u32 = 100;
+
+/// Most memory a decoded video may take; sources are held whole, so longer clips are refused rather than exhausting RAM.
+pub const MAX_SOURCE_BYTES: usize = 2 << 30;
+
+/// Browsers play GIF delays of this or less as `DEFAULT_DELAY_MS`; we match them so exports look identical.
+const GIF_MIN_DELAY_MS: u32 = 10;
+
+/// How many times an animation plays in total before stopping.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LoopCount {
+    #[default]
+    Forever,
+    Times(u32),
+}
+
+/// Per-frame display durations and loop behaviour of a source.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Timing {
+    pub delays_ms: Vec<u32>,
+    pub loop_count: LoopCount,
+}
+
+impl Timing {
+    pub fn delay_ms(&self, frame: usize) -> u32 {
+        self.delays_ms.get(frame).copied().unwrap_or(DEFAULT_DELAY_MS)
+    }
+}
+
+/// Decoded frames, kept in a single layout so a long clip is held in memory once.
+pub enum SourceFrames {
+    /// Sources without color, such as mono Y4M streams.
+    Gray(Vec<GrayImage>),
+    /// Alpha already flattened; luma is derived from these when a gray tensor is built.
+    Rgb(Vec<RgbImage>),
+}
+
+impl SourceFrames {
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Gray(frames) => frames.len(),
+            Self::Rgb(frames) => frames.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Width and height shared by every frame.
+    pub fn dimensions(&self) -> (u32, u32) {
+        match self {
+            Self::Gray(frames) => frames[0].dimensions(),
+            Self::Rgb(frames) => frames[0].dimensions(),
+        }
+    }
+}
+
+pub struct DecodedMedia {
+    pub frames: SourceFrames,
+    pub timing: Timing,
+}
+
+/// Sniff the container from its magic bytes rather than trusting the extension.
+pub fn decode(path: &str, monitor: &LoadMonitor) -> Result<DecodedMedia, String> {
+    if crate::y4m::sniff(path)? { return crate::y4m::decode(path, monitor); }
+    let reader = ImageReader::new(open_tracked(path, monitor)?)
+        .with_guessed_format().map_err(|e| e.to_string())?;
+    match reader.format() {
+        Some(ImageFormat::Gif) => decode_gif(path, monitor),
+        Some(ImageFormat::Png) => decode_png(path, monitor),
+        Some(ImageFormat::WebP) => decode_webp(path, monitor),
+        Some(_) => decode_still(reader, monitor),
+        None => Err("Unrecognized media format".into()),
+    }
+}
+
+fn decode_still(reader: ImageReader<BufReader<Tracked<File>>>, monitor: &LoadMonitor) -> Result<DecodedMedia, String> {
+    let media = still(reader.decode().map_err(|e| e.to_string())?);
+    monitor.decoded_of(1, 1)?;
+    Ok(media)
+}
+
+fn still(image: DynamicImage) -> DecodedMedia {
+    let rgba = image.into_rgba8();
+    DecodedMedia {
+        frames: SourceFrames::Rgb(vec![rgb_from_rgba(&rgba)]),
+        timing: Timing { delays_ms: vec![DEFAULT_DELAY_MS], loop_count: LoopCount::Forever },
+    }
+}
+
+pub fn decode_gif(path: &str, monitor: &LoadMonitor) -> Result<DecodedMedia, String> {
+    let loop_count = gif_loop_count(path)?;
+    let decoder = GifDecoder::new(open_tracked(path, monitor)?).map_err(|e| e.to_string())?;
+    let frames = collect_frames(decoder.into_frames(), monitor)?;
+    Ok(split_frames(frames, loop_count, |ms| if ms <= GIF_MIN_DELAY_MS { DEFAULT_DELAY_MS } else { ms }))
+}
+
+/// APNG goes through the animation path; plain PNG is decoded as a still.
+fn decode_png(path: &str, monitor: &LoadMonitor) -> Result<DecodedMedia, String> {
+    let decoder = PngDecoder::new(open_tracked(path, monitor)?).map_err(|e| e.to_string())?;
+    if !decoder.is_apng().map_err(|e| e.to_string())? {
+        let media = still(DynamicImage::from_decoder(decoder).map_err(|e| e.to_string())?);
+        monitor.decoded_of(1, 1)?;
+        return Ok(media);
+    }
+    let loop_count = apng_loop_count(path)?;
+    let frames = collect_frames(decoder.apng().map_err(|e| e.to_string())?.into_frames(), monitor)?;
+    Ok(split_frames(frames, loop_count, |ms| ms.max(1)))
+}
+
+fn apng_loop_count(path: &str) -> Result<LoopCount, String> {
+    let reader = png::Decoder::new(open(path)?).read_info().map_err(|e| e.to_string())?;
+    Ok(match reader.info().animation_control.map(|control| control.num_plays) {
+        None | Some(0) => LoopCount::Forever,
+        Some(plays) => LoopCount::Times(plays),
+    })
+}
+
+fn decode_webp(path: &str, monitor: &LoadMonitor) -> Result<DecodedMedia, String> {
+    let decoder = WebPDecoder::new(open_tracked(path, monitor)?).map_err(|e| e.to_string())?;
+    if !decoder.has_animation() {
+        let media = still(DynamicImage::from_decoder(decoder).map_err(|e| e.to_string())?);
+        monitor.decoded_of(1, 1)?;
+        return Ok(media);
+    }
+    // `image` hides the ANIM chunk, so ask `image-webp` for the loop count directly.
+    let loop_count = match image_webp::WebPDecoder::new(open(path)?).map_err(|e| e.to_string())?.loop_count() {
+        image_webp::LoopCount::Forever => LoopCount::Forever,
+        image_webp::LoopCount::Times(plays) => LoopCount::Times(plays.get() as u32),
+    };
+    let frames = collect_frames(decoder.into_frames(), monitor)?;
+    Ok(split_frames(frames, loop_count, |ms| ms.max(1)))
+}
+
+/// Like `Frames::collect_frames`, but reports each frame and stops as soon as the load is cancelled.
+fn collect_frames(frames: Frames, monitor: &LoadMonitor) -> Result<Vec<Frame>, String> {
+    let mut collected = Vec::new();
+    for frame in frames {
+        collected.push(frame.map_err(|e| e.to_string())?);
+        monitor.decoded(collected.len())?;
+    }
+    Ok(collected)
+}
+
+/// Build an animation from a directory of stills or a glob such as `renders/frame_*.png`.
+/// Files play in natural order (`frame_2` before `frame_10`) at a fixed `fps`. Frames whose size
+/// differs from the first are letterboxed onto its canvas when `letterbox` is set, otherwise rejected.
+pub fn decode_sequence(pattern: &str, fps: f32, letterbox: bool, monitor: &LoadMonitor) -> Result<DecodedMedia, String> {
+    if !(fps.is_finite() && fps > 0.0) { return Err(format!("Invalid frame rate: {}", fps)); }
+    let mut paths = sequence_paths(pattern)?;
+    if paths.is_empty() { return Err(format!("No images match {}", pattern)); }
+    paths.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+
+    let done = AtomicUsize::new(0);
+    let mut rgba_frames: Vec<RgbaImage> = paths.par_iter().map(|path| {
+        monitor.check()?;
+        let reader = ImageReader::open(path).map_err(|e| e.to_string())?
+            .with_guessed_format().map_err(|e| e.to_string())?;
+        let frame = reader.decode().map(|image| image.into_rgba8()).map_err(|e| format!("{}: {}", path.display(), e))?;
+        monitor.decoded_of(done.fetch_add(1, AtomicOrdering::Relaxed) + 1, paths.len())?;
+        Ok(frame)
+    }).collect::<Result<_, String>>()?;
+
+    let (canvas_w, canvas_h) = rgba_frames[0].dimensions();
+    for (frame, path) in rgba_frames.iter_mut().zip(&paths) {
+        if frame.dimensions() == (canvas_w, canvas_h) { continue; }
+        if !letterbox {
+            let (w, h) = frame.dimensions();
+            return Err(format!("{} is {}x{}, expected {}x{}", path.display(), w, h, canvas_w, canvas_h));
+        }
+        *frame = letterbox_onto(frame, canvas_w, canvas_h);
+    }
+
+    let frames: Vec<RgbImage> = rgba_frames.par_iter().map(rgb_from_rgba).collect();
+    let delay_ms = ((1000.0 / fps).round() as u32).max(1);
+    let delays_ms = vec![delay_ms; frames.len()];
+    Ok(DecodedMedia { frames: SourceFrames::Rgb(frames), timing: Timing { delays_ms, loop_count: LoopCount::Forever } })
+}
+
+fn sequence_paths(pattern: &str) -> Result<Vec<PathBuf>, String> {
+    let dir = Path::new(pattern);
+    if dir.is_dir() {
+        let entries = std::fs::read_dir(dir).map_err(|e| e.to_string())?;
+        return Ok(entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && ImageFormat::from_path(path).is_ok())
+            .collect());
+    }
+    let matches = glob::glob(pattern).map_err(|e| e.to_string())?;
+    Ok(matches.filter_map(Result::ok).filter(|path| path.is_file()).collect())
+}
+
+/// Scale to fit inside the canvas and centre it; the transparent bars read as blank cells.
+fn letterbox_onto(frame: &RgbaImage, canvas_w: u32, canvas_h: u32) -> RgbaImage {
+    let scale = (canvas_w as f32 / frame.width() as f32).min(canvas_h as f32 / frame.height() as f32);
+    let w = ((frame.width() as f32 * scale).round() as u32).clamp(1, canvas_w);
+    let h = ((frame.height() as f32 * scale).round() as u32).clamp(1, canvas_h);
+    let scaled = imageops::resize(frame, w, h, FilterType::Triangle);
+    let mut canvas = RgbaImage::new(canvas_w, canvas_h);
+    imageops::overlay(&mut canvas, &scaled, ((canvas_w - w) / 2) as i64, ((canvas_h - h) / 2) as i64);
+    canvas
+}
+
+/// Compare strings treating runs of ASCII digits as numbers, so `frame_9` sorts before `frame_10`.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.as_bytes(), b.as_bytes());
+    loop {
+        match (a.first(), b.first()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let a_len = a.iter().take_while(|c| c.is_ascii_digit()).count();
+                let b_len = b.iter().take_while(|c| c.is_ascii_digit()).count();
+                let (a_num, b_num) = (trim_zeros(&a[..a_len]), trim_zeros(&b[..b_len]));
+                let ord = a_num.len().cmp(&b_num.len()).then_with(|| a_num.cmp(b_num));
+                if ord != Ordering::Equal { return ord; }
+                a = &a[a_len..];
+                b = &b[b_len..];
+            }
+            (Some(x), Some(y)) => {
+                if x != y { return x.cmp(y); }
+                a = &a[1..];
+                b = &b[1..];
+            }
+        }
+    }
+}
+
+fn trim_zeros(digits: &[u8]) -> &[u8] {
+    let zeros = digits.iter().take_while(|&&c| c == b'0').count();
+    &digits[zeros..]
+}
+
+fn open(path: &str) -> Result<BufReader<File>, String> {
+    File::open(path).map(BufReader::new).map_err(|e| e.to_string())
+}
+
+fn open_tracked(path: &str, monitor: &LoadMonitor) -> Result<BufReader<Tracked<File>>, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    Ok(BufReader::new(monitor.track(file)?))
+}
+
+/// `image` drops the NETSCAPE2.0 extension, so read it with a metadata-only pass of the `gif` crate.
+fn gif_loop_count(path: &str) -> Result<LoopCount, String> {
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = options.read_info(open(path)?).map_err(|e| e.to_string())?;
+    // The application extension precedes the first image descriptor.
+    decoder.next_frame_info().map_err(|e| e.to_string())?;
+    Ok(match decoder.repeat() {
+        gif::Repeat::Infinite => LoopCount::Forever,
+        gif::Repeat::Finite(repeats) => LoopCount::Times(repeats as u32 + 1),
+    })
+}
+
+/// `normalize` is the source format's rule for delays players would not show as written.
+fn split_frames(frames: Vec<Frame>, loop_count: LoopCount, normalize: impl Fn(u32) -> u32) -> DecodedMedia {
+    let delays_ms = frames.iter().map(|frame| {
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        normalize(numer.div_ceil(denom.max(1)))
+    }).collect();
+    let frames = frames.par_iter().map(|frame| rgb_from_rgba(frame.buffer())).collect();
+    DecodedMedia { frames: SourceFrames::Rgb(frames), timing: Timing { delays_ms, loop_count } }
+}
+
+/// Rec. 601 luma in 16.16 fixed point.
+pub fn luma_from_rgb(rgb: &RgbImage) -> GrayImage {
+    let luma = rgb.as_raw().chunks_exact(3).map(|p| {
+        ((p[0] as u32 * 19595 + p[1] as u32 * 38470 + p[2] as u32 * 7471) >> 16) as u8
+    }).collect();
+    GrayImage::from_raw(rgb.width(), rgb.height(), luma).expect("luma buffer matches source dimensions")
+}
+
+/// Mostly transparent pixels become white so they render blank.
+pub fn rgb_from_rgba(rgba: &RgbaImage) -> RgbImage {
+    let rgb = rgba.as_raw().chunks_exact(4).flat_map(|p| if p[3] < 128 { [255; 3] } else { [p[0], p[1], p[2]] }).collect();
+    RgbImage::from_raw(rgba.width(), rgba.height(), rgb).expect("rgb buffer matches source dimensions")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_digit_runs_by_value() {
+        let mut names = vec!["frame_10.png", "frame_9.png", "frame_1.png", "frame_100.png", "frame_2.png"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, ["frame_1.png", "frame_2.png", "frame_9.png", "frame_10.png", "frame_100.png"]);
+    }
+
+    #[test]
+    fn ignores_leading_zeros() {
+        assert_eq!(natural_cmp("img007", "img7"), Ordering::Equal);
+        assert_eq!(natural_cmp("img007", "img10"), Ordering::Less);
+        assert_eq!(natural_cmp("img0", "img00"), Ordering::Equal);
+    }
+
+    #[test]
+    fn compares_text_bytewise_around_numbers() {
+        assert_eq!(natural_cmp("a2b", "a2c"), Ordering::Less);
+        assert_eq!(natural_cmp("b1", "a10"), Ordering::Greater);
+        assert_eq!(natural_cmp("shot", "shot1"), Ordering::Less);
+        assert_eq!(natural_cmp("shot_1_2", "shot_1_10"), Ordering::Less);
+    }
+
+    #[test]
+    fn handles_numbers_longer_than_any_integer() {
+        assert_eq!(natural_cmp("f99999999999999999999999", "f100000000000000000000000"), Ordering::Less);
+    }
+}
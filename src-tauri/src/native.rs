@@ -0,0 +1,273 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Save rendered animations as `.ascii` files that can be reopened, replayed and re-exported.
+//!
+//! The file is plain UTF-8 so it diffs well in a repository:
+//!
+//! ```text
+//! ASCII-STUDIO 1
+//! columns: 100
+//! rows: 50
+//! frames: 2
+//! loop: "forever"
+//! delays: [40,40]
+//! source: "clip.gif"
+//! ramp: "@%#*+=-:. "
+//! settings: {"width":100,...}
+//!
+//! frame 0
+//! <rows lines>
+//! frame 1
+//! <rows lines>
+//! ```
+//!
+//! Header values are JSON and unknown keys are skipped, so later versions can add to it.
+
+use crate::export::{FrameSink, Header};
+use crate::media::{LoopCount, Timing};
+use crate::ramp::Ramp;
+use crate::render::RenderSettings;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+
+const MAGIC: &str = "ASCII-STUDIO";
+
+/// Bumped whenever a reader of the previous version would misread a new file.
+const VERSION: u32 = 1;
+
+/// A saved animation, frames exactly as they were rendered.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Animation {
+    pub columns: usize,
+    pub rows: usize,
+    pub frame_count: usize,
+    #[serde(flatten)]
+    pub timing: Timing,
+    pub source: String,
+    /// The ramp's glyphs, densest first.
+    pub ramp: String,
+    pub settings: RenderSettings,
+    /// Frame `i` is `data[offsets[i]..offsets[i + 1]]`.
+    pub data: Vec<u8>,
+    pub offsets: Vec<usize>,
+}
+
+impl Animation {
+    pub fn header(&self) -> Header<'_> {
+        Header {
+            columns: self.columns,
+            rows: self.rows,
+            frame_count: self.frame_count,
+            timing: &self.timing,
+            source: &self.source,
+            settings: &self.settings,
+        }
+    }
+
+    pub fn frame(&self, index: usize) -> &[u8] {
+        &self.data[self.offsets[index]..self.offsets[index + 1]]
+    }
+}
+
+pub struct NativeSink<W: Write> {
+    out: W,
+}
+
+impl<W: Write> NativeSink<W> {
+    pub fn open(header: &Header, mut out: W) -> io::Result<Self> {
+        let ramp: String = Ramp::new(&header.settings.ramp).map_err(io::Error::other)?.glyphs().iter().collect();
+        writeln!(out, "{} {}", MAGIC, VERSION)?;
+        writeln!(out, "columns: {}", header.columns)?;
+        writeln!(out, "rows: {}", header.rows)?;
+        writeln!(out, "frames: {}", header.frame_count)?;
+        writeln!(out, "loop: {}", serde_json::to_string(&header.timing.loop_count)?)?;
+        let delays: Vec<u32> = (0..header.frame_count).map(|i| header.timing.delay_ms(i)).collect();
+        writeln!(out, "delays: {}", serde_json::to_string(&delays)?)?;
+        writeln!(out, "source: {}", serde_json::to_string(header.source)?)?;
+        writeln!(out, "ramp: {}", serde_json::to_string(&ramp)?)?;
+        writeln!(out, "settings: {}", serde_json::to_string(header.settings)?)?;
+        writeln!(out)?;
+        Ok(Self { out })
+    }
+}
+
+impl<W: Write> FrameSink for NativeSink<W> {
+    fn frame(&mut self, index: usize, _delay_ms: u32, text: &[u8]) -> io::Result<()> {
+        writeln!(self.out, "frame {}", index)?;
+        self.out.write_all(text)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+pub fn read(path: &str) -> Result<Animation, String> {
+    let mut reader = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
+    let mut line = Vec::new();
+
+    let magic = next_line(&mut reader, &mut line)?.ok_or("Not an ASCII Studio animation")?;
+    let version = std::str::from_utf8(magic).ok()
+        .and_then(|magic| magic.strip_prefix(MAGIC)?.trim().parse::<u32>().ok())
+        .ok_or("Not an ASCII Studio animation")?;
+    if version > VERSION { return Err(format!("Saved by a newer ASCII Studio (format {})", version)); }
+
+    let (mut columns, mut rows, mut frame_count) = (None, None, None);
+    let (mut loop_count, mut delays_ms) = (LoopCount::default(), Vec::new());
+    let (mut source, mut ramp, mut settings) = (String::new(), String::new(), RenderSettings::default());
+    while let Some(entry) = next_line(&mut reader, &mut line)? {
+        if entry.is_empty() { break; }
+        let entry = std::str::from_utf8(entry).map_err(|_| "Animation header is not UTF-8")?;
+        let (key, value) = entry.split_once(": ").ok_or_else(|| format!("Bad animation header line: {}", entry))?;
+        let bad_value = |e: serde_json::Error| format!("Bad animation header value for {}: {}", key, e);
+        match key {
+            "columns" => columns = Some(serde_json::from_str(value).map_err(bad_value)?),
+            "rows" => rows = Some(serde_json::from_str(value).map_err(bad_value)?),
+            "frames" => frame_count = Some(serde_json::from_str(value).map_err(bad_value)?),
+            "loop" => loop_count = serde_json::from_str(value).map_err(bad_value)?,
+            "delays" => delays_ms = serde_json::from_str(value).map_err(bad_value)?,
+            "source" => source = serde_json::from_str(value).map_err(bad_value)?,
+            "ramp" => ramp = serde_json::from_str(value).map_err(bad_value)?,
+            "settings" => settings = serde_json::from_str(value).map_err(bad_value)?,
+            _ => {}
+        }
+    }
+    let columns = columns.ok_or("Animation header is missing its columns")?;
+    let rows: usize = rows.ok_or("Animation header is missing its rows")?;
+    let frame_count: usize = frame_count.ok_or("Animation header is missing its frame count")?;
+
+    let mut data = Vec::new();
+    let mut offsets = vec![0];
+    for index in 0..frame_count {
+        let marker = next_line(&mut reader, &mut line)?.ok_or("Truncated animation")?;
+        if marker != format!("frame {}", index).as_bytes() {
+            return Err(format!("Corrupt animation: expected frame {}", index));
+        }
+        for _ in 0..rows {
+            data.extend_from_slice(next_line(&mut reader, &mut line)?.ok_or("Truncated animation")?);
+            data.push(b'\n');
+        }
+        offsets.push(data.len());
+    }
+    Ok(Animation {
+        columns,
+        rows,
+        frame_count,
+        timing: Timing { delays_ms, loop_count },
+        source,
+        ramp,
+        settings,
+        data,
+        offsets,
+    })
+}
+
+/// The next line without its ending, or `None` at the end of the file. Checkouts may have turned `\n` into `\r\n`.
+fn next_line<'a>(reader: &mut impl BufRead, line: &'a mut Vec<u8>) -> Result<Option<&'a [u8]>, String> {
+    line.clear();
+    if reader.read_until(b'\n', line).map_err(|e| e.to_string())? == 0 { return Ok(None); }
+    let mut end = line.len();
+    if line[..end].ends_with(b"\n") { end -= 1; }
+    if line[..end].ends_with(b"\r") { end -= 1; }
+    Ok(Some(&line[..end]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{CacheConfig, CacheKey, CellAspect, Resample, WidthCache};
+    use crate::export::{self, ExportFormat, ExportOptions, Job};
+    use crate::media::SourceFrames;
+    use crate::render::{self, RenderMode, Rendered};
+    use image::{Rgb, RgbImage};
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ascii-studio-{}-{}", std::process::id(), name))
+    }
+
+    /// Three colored gradient frames rendered with `settings`, saved as `.ascii` bytes.
+    fn saved(settings: &RenderSettings, timing: &Timing) -> (Rendered, Vec<u8>) {
+        let frames = (0..3u32)
+            .map(|f| RgbImage::from_fn(64, 48, |x, y| Rgb([(x * 4) as u8, (y * 5) as u8, (f * 80) as u8])))
+            .collect();
+        let cache = WidthCache::new(SourceFrames::Rgb(frames), CacheConfig::default());
+        let key = CacheKey {
+            width: settings.width,
+            filter: Resample::Box,
+            cell_aspect: CellAspect::DEFAULT,
+            grid: settings.mode.grid(),
+            rgb: false,
+        };
+        let tensor = cache.get(key).unwrap();
+        let colors = cache.get(CacheKey { rgb: true, ..key }).unwrap();
+        let rendered = render::render(&tensor, Some(&colors), &[0, 1, 2], settings, None).unwrap();
+        let job = Job { tensor: &tensor, colors: Some(&colors), settings, font: None, timing, source: "clip.gif" };
+        let mut bytes = Vec::new();
+        export::export(&job, ExportFormat::Native, &ExportOptions::default(), &mut bytes).unwrap();
+        (rendered, bytes)
+    }
+
+    fn read_bytes(name: &str, bytes: &[u8]) -> Result<Animation, String> {
+        let path = temp_path(name);
+        std::fs::write(&path, bytes).unwrap();
+        let animation = read(path.to_str().unwrap());
+        let _ = std::fs::remove_file(&path);
+        animation
+    }
+
+    fn colored_settings() -> RenderSettings {
+        RenderSettings { width: 24, color: true, mode: RenderMode::HalfBlock, ..RenderSettings::default() }
+    }
+
+    fn timing() -> Timing {
+        Timing { delays_ms: vec![40, 70, 100], loop_count: LoopCount::Times(3) }
+    }
+
+    #[test]
+    fn round_trips_colored_frames() {
+        let (settings, timing) = (colored_settings(), timing());
+        let (rendered, bytes) = saved(&settings, &timing);
+        assert!(rendered.data.contains(&0x1b), "frames should carry ANSI colors");
+
+        let animation = read_bytes("round-trip.ascii", &bytes).unwrap();
+        assert_eq!(animation.data, rendered.data);
+        assert_eq!(animation.offsets, rendered.offsets);
+        assert_eq!(animation.frame_count, 3);
+        assert_eq!(animation.timing.delays_ms, timing.delays_ms);
+        assert_eq!(animation.timing.loop_count, timing.loop_count);
+        assert_eq!(animation.source, "clip.gif");
+        assert_eq!(animation.settings.width, settings.width);
+        assert_eq!(animation.settings.mode, settings.mode);
+    }
+
+    #[test]
+    fn reads_crlf_checkouts() {
+        let (rendered, bytes) = saved(&colored_settings(), &timing());
+        let crlf = String::from_utf8(bytes).unwrap().replace('\n', "\r\n");
+
+        let animation = read_bytes("crlf.ascii", crlf.as_bytes()).unwrap();
+        assert_eq!(animation.data, rendered.data);
+        assert_eq!(animation.offsets, rendered.offsets);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let (_, bytes) = saved(&colored_settings(), &timing());
+        let newer = String::from_utf8(bytes).unwrap().replacen("ASCII-STUDIO 1", "ASCII-STUDIO 2", 1);
+
+        let error = read_bytes("newer.ascii", newer.as_bytes()).err().unwrap();
+        assert_eq!(error, "Saved by a newer ASCII Studio (format 2)");
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        let (_, bytes) = saved(&colored_settings(), &timing());
+        let cut = bytes.iter().rposition(|&b| b == b'f').unwrap();
+
+        let error = read_bytes("truncated.ascii", &bytes[..cut]).err().unwrap();
+        assert_eq!(error, "Truncated animation");
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Snap colors to what a terminal can show, matching indexed palettes in OKLab so the nearest color looks nearest.
+
+use crate::ansi::Rgb;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Which colors the ANSI output may use.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Palette {
+    /// 24-bit `38;2;r;g;b` escapes.
+    #[default]
+    TrueColor,
+    /// The xterm 6x6x6 cube and gray ramp, indices 16-255.
+    Xterm256,
+    /// The eight standard colors and their bright variants.
+    Ansi16,
+}
+
+/// A color as the terminal is told about it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    True(Rgb),
+    Xterm(u8),
+    Ansi(u8),
+}
+
+/// xterm's defaults; terminals theme these, so they are only a guess at what will be shown.
+const ANSI16: [Rgb; 16] = [
+    [0, 0, 0], [205, 0, 0], [0, 205, 0], [205, 205, 0], [0, 0, 238], [205, 0, 205], [0, 205, 205], [229, 229, 229],
+    [127, 127, 127], [255, 0, 0], [0, 255, 0], [255, 255, 0], [92, 92, 255], [255, 0, 255], [0, 255, 255], [255, 255, 255],
+];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Bits kept per channel when looking up the nearest indexed color.
+const LUT_BITS: u32 = 5;
+
+impl Palette {
+    pub fn snap(self, rgb: Rgb) -> Color {
+        match self {
+            Self::TrueColor => Color::True(rgb),
+            Self::Xterm256 => {
+                static LUT: OnceLock<Vec<u8>> = OnceLock::new();
+                Color::Xterm(16 + LUT.get_or_init(|| nearest_lut(&xterm256()))[lut_index(rgb)])
+            }
+            Self::Ansi16 => {
+                static LUT: OnceLock<Vec<u8>> = OnceLock::new();
+                Color::Ansi(LUT.get_or_init(|| nearest_lut(&ANSI16))[lut_index(rgb)])
+            }
+        }
+    }
+
+    /// The palette color `rgb` snaps to.
+    pub fn nearest(self, rgb: Rgb) -> Rgb {
+        match self.snap(rgb) {
+            Color::True(rgb) => rgb,
+            Color::Xterm(index) => xterm_color(index),
+            Color::Ansi(index) => ANSI16[index as usize],
+        }
+    }
+
+    /// Rough gap between neighbouring palette colors along one channel, for scaling ordered dithering.
+    pub fn spread(self) -> f32 {
+        match self {
+            Self::TrueColor => 1.0,
+            Self::Xterm256 => 40.0,
+            Self::Ansi16 => 128.0,
+        }
+    }
+}
+
+impl Color {
+    pub fn write(self, background: bool, out: &mut Vec<u8>) {
+        let escape = match (self, background) {
+            (Self::True([r, g, b]), false) => format!("\x1b[38;2;{};{};{}m", r, g, b),
+            (Self::True([r, g, b]), true) => format!("\x1b[48;2;{};{};{}m", r, g, b),
+            (Self::Xterm(index), false) => format!("\x1b[38;5;{}m", index),
+            (Self::Xterm(index), true) => format!("\x1b[48;5;{}m", index),
+            (Self::Ansi(index), false) => format!("\x1b[{}m", if index < 8 { 30 + index } else { 82 + index }),
+            (Self::Ansi(index), true) => format!("\x1b[{}m", if index < 8 { 40 + index } else { 92 + index }),
+        };
+        out.extend_from_slice(escape.as_bytes());
+    }
+}
+
+/// What an 8-bit palette index shows as in xterm by default.
+pub fn indexed_color(index: u8) -> Rgb {
+    if index < 16 { ANSI16[index as usize] } else { xterm_color(index) }
+}
+
+/// Palette indices 16-255; the first 16 repeat the themeable colors, so they are left out.
+fn xterm256() -> Vec<Rgb> {
+    (16..=255).map(xterm_color).collect()
+}
+
+/// An entry of the 6x6x6 cube (16-231) or the gray ramp (232-255).
+fn xterm_color(index: u8) -> Rgb {
+    match index as usize {
+        i @ 16..=231 => {
+            let i = i - 16;
+            [CUBE_LEVELS[i / 36], CUBE_LEVELS[i / 6 % 6], CUBE_LEVELS[i % 6]]
+        }
+        i => [(8 + 10 * i.saturating_sub(232)) as u8; 3],
+    }
+}
+
+fn lut_index([r, g, b]: Rgb) -> usize {
+    let shift = 8 - LUT_BITS;
+    ((r as usize >> shift) << (2 * LUT_BITS)) | ((g as usize >> shift) << LUT_BITS) | (b as usize >> shift)
+}
+
+/// The closest entry of `colors` for every reduced RGB value, by squared OKLab distance.
+fn nearest_lut(colors: &[Rgb]) -> Vec<u8> {
+    let labs: Vec<[f32; 3]> = colors.iter().map(|&rgb| oklab(rgb)).collect();
+    let expand = |bits: usize| ((bits << (8 - LUT_BITS)) | (bits >> (2 * LUT_BITS - 8))) as u8;
+    let mask = (1 << LUT_BITS) - 1;
+    (0..1usize << (3 * LUT_BITS)).map(|i| {
+        let rgb = [expand(i >> (2 * LUT_BITS)), expand((i >> LUT_BITS) & mask), expand(i & mask)];
+        let lab = oklab(rgb);
+        let distance = |other: &[f32; 3]| (0..3).map(|c| (lab[c] - other[c]).powi(2)).sum::<f32>();
+        (0..labs.len()).min_by(|&a, &b| distance(&labs[a]).total_cmp(&distance(&labs[b]))).unwrap_or(0) as u8
+    }).collect()
+}
+
+/// Björn Ottosson's OKLab, from gamma-encoded sRGB.
+fn oklab(rgb: Rgb) -> [f32; 3] {
+    let [r, g, b] = rgb.map(|c| {
+        let c = c as f32 / 255.0;
+        if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    });
+    let l = (0.4122215 * r + 0.5363325 * g + 0.05144599 * b).cbrt();
+    let m = (0.2119035 * r + 0.6806995 * g + 0.107397 * b).cbrt();
+    let s = (0.08830246 * r + 0.2817188 * g + 0.6299787 * b).cbrt();
+    [
+        0.2104543 * l + 0.7936178 * m - 0.004072047 * s,
+        1.977998 * l - 2.428592 * m + 0.4505937 * s,
+        0.02590404 * l + 0.7827718 * m - 0.8086758 * s,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snaps_primaries_to_the_xterm_cube() {
+        assert_eq!(Palette::Xterm256.snap([0, 0, 0]), Color::Xterm(16));
+        assert_eq!(Palette::Xterm256.snap([255, 0, 0]), Color::Xterm(196));
+        assert_eq!(Palette::Xterm256.snap([0, 255, 0]), Color::Xterm(46));
+        assert_eq!(Palette::Xterm256.snap([0, 0, 255]), Color::Xterm(21));
+        assert_eq!(Palette::Xterm256.snap([255, 255, 255]), Color::Xterm(231));
+        assert_eq!(Palette::Xterm256.nearest([250, 5, 5]), [255, 0, 0]);
+    }
+
+    #[test]
+    fn snaps_to_the_16_color_palette() {
+        assert_eq!(Palette::Ansi16.snap([0, 0, 0]), Color::Ansi(0));
+        assert_eq!(Palette::Ansi16.snap([200, 0, 0]), Color::Ansi(1));
+        assert_eq!(Palette::Ansi16.snap([255, 0, 0]), Color::Ansi(9));
+        assert_eq!(Palette::Ansi16.snap([0, 255, 255]), Color::Ansi(14));
+        assert_eq!(Palette::Ansi16.snap([255, 255, 255]), Color::Ansi(15));
+    }
+
+    #[test]
+    fn maps_indices_to_xterm_defaults() {
+        assert_eq!(indexed_color(9), [255, 0, 0]);
+        assert_eq!(indexed_color(16 + 36 + 6 * 2 + 3), [95, 135, 175]);
+        assert_eq!(indexed_color(232), [8, 8, 8]);
+        assert_eq!(indexed_color(255), [238, 238, 238]);
+    }
+
+    #[test]
+    fn writes_the_escape_for_each_palette() {
+        let escape = |color: Color, background: bool| {
+            let mut out = Vec::new();
+            color.write(background, &mut out);
+            String::from_utf8(out).unwrap()
+        };
+        assert_eq!(escape(Color::True([1, 2, 3]), false), "\x1b[38;2;1;2;3m");
+        assert_eq!(escape(Color::Xterm(196), true), "\x1b[48;5;196m");
+        assert_eq!(escape(Color::Ansi(1), false), "\x1b[31m");
+        assert_eq!(escape(Color::Ansi(9), false), "\x1b[91m");
+        assert_eq!(escape(Color::Ansi(15), true), "\x1b[107m");
+    }
+}
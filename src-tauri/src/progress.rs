@@ -0,0 +1,147 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Report load progress to the frontend and let a newer load or `cancel_load` abort the current one.
+
+use serde::Serialize;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const CANCELLED: &str = "Load cancelled";
+
+/// Reports closer together than this are dropped, except the last one of each stage.
+const REPORT_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "stage")]
+pub enum LoadProgress {
+    /// `fraction` is the share of the input consumed so far.
+    #[serde(rename_all = "camelCase")]
+    Decoding { frames: usize, fraction: f32, eta_ms: Option<u64> },
+    #[serde(rename_all = "camelCase")]
+    Caching { built: usize, total: usize, eta_ms: Option<u64> },
+}
+
+#[derive(Clone, Default)]
+pub struct LoadToken(Arc<AtomicBool>);
+
+impl LoadToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn same_as(&self, other: &LoadToken) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+struct Clock {
+    stage_start: Instant,
+    last_report: Option<Instant>,
+}
+
+/// Threaded through the decoders; every report doubles as a cancellation check.
+pub struct LoadMonitor {
+    token: LoadToken,
+    report: Box<dyn Fn(LoadProgress) + Send + Sync>,
+    bytes_read: Arc<AtomicU64>,
+    total_bytes: AtomicU64,
+    clock: Mutex<Clock>,
+}
+
+impl LoadMonitor {
+    pub fn new(token: LoadToken, report: impl Fn(LoadProgress) + Send + Sync + 'static) -> Self {
+        Self {
+            token,
+            report: Box::new(report),
+            bytes_read: Arc::default(),
+            total_bytes: AtomicU64::new(0),
+            clock: Mutex::new(Clock { stage_start: Instant::now(), last_report: None }),
+        }
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        if self.token.is_cancelled() { Err(CANCELLED.into()) } else { Ok(()) }
+    }
+
+    /// Wrap the file holding the bulk of the input so `decoded` can derive a fraction from it.
+    pub fn track(&self, file: File) -> Result<Tracked<File>, String> {
+        let len = file.metadata().map_err(|e| e.to_string())?.len();
+        self.total_bytes.store(len, Ordering::Relaxed);
+        self.bytes_read.store(0, Ordering::Relaxed);
+        Ok(Tracked { inner: file, position: Arc::clone(&self.bytes_read) })
+    }
+
+    pub fn decoded(&self, frames: usize) -> Result<(), String> {
+        let total = self.total_bytes.load(Ordering::Relaxed).max(1);
+        let fraction = (self.bytes_read.load(Ordering::Relaxed) as f32 / total as f32).min(1.0);
+        self.decoded_fraction(frames, fraction)
+    }
+
+    pub fn decoded_of(&self, frames: usize, total: usize) -> Result<(), String> {
+        self.decoded_fraction(frames, frames as f32 / total.max(1) as f32)
+    }
+
+    pub fn cached(&self, built: usize, total: usize) -> Result<(), String> {
+        self.check()?;
+        if built == 0 { self.restart_clock(); }
+        let fraction = built as f32 / total.max(1) as f32;
+        if let Some(eta_ms) = self.due(fraction) {
+            (self.report)(LoadProgress::Caching { built, total, eta_ms });
+        }
+        Ok(())
+    }
+
+    fn decoded_fraction(&self, frames: usize, fraction: f32) -> Result<(), String> {
+        self.check()?;
+        if let Some(eta_ms) = self.due(fraction) {
+            (self.report)(LoadProgress::Decoding { frames, fraction, eta_ms });
+        }
+        Ok(())
+    }
+
+    fn restart_clock(&self) {
+        if let Ok(mut clock) = self.clock.lock() {
+            *clock = Clock { stage_start: Instant::now(), last_report: None };
+        }
+    }
+
+    /// Rate-limit reports; the outer option says whether to send, the inner one is the ETA.
+    fn due(&self, fraction: f32) -> Option<Option<u64>> {
+        let mut clock = self.clock.lock().ok()?;
+        let now = Instant::now();
+        let finished = fraction >= 1.0;
+        if !finished && clock.last_report.is_some_and(|last| now - last < REPORT_INTERVAL) { return None; }
+        clock.last_report = Some(now);
+        let elapsed = (now - clock.stage_start).as_secs_f32();
+        Some((fraction > 0.0).then(|| (elapsed * (1.0 - fraction) / fraction * 1000.0) as u64))
+    }
+}
+
+/// Passes reads through while publishing the stream position for progress reporting.
+pub struct Tracked<R> {
+    inner: R,
+    position: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for Tracked<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.position.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+impl<R: Seek> Seek for Tracked<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let position = self.inner.seek(pos)?;
+        self.position.store(position, Ordering::Relaxed);
+        Ok(position)
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Character ramps that map cell brightness to glyphs, listed densest first.
+
+use crate::font;
+use ab_glyph::FontVec;
+use serde::{Deserialize, Serialize};
+
+/// Longest custom ramp accepted; one glyph per gray level is already the most that can show.
+const MAX_GLYPHS: usize = 256;
+
+/// Printable ASCII, the candidates measured when the caller names none.
+const DEFAULT_CANDIDATES: &str = " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RampPreset {
+    Short,
+    #[default]
+    Standard,
+    Blocks,
+    Digits,
+    Binary,
+}
+
+impl RampPreset {
+    pub fn glyphs(self) -> &'static str {
+        match self {
+            Self::Short => "@%#*+=-:. ",
+            Self::Standard => "$@B%8&WM#*oahkbdpqwmZO0QLCJUYXzcvunxrjft/\\|()1{}[]?-_+~<>i!lI;:,\"^`'. ",
+            Self::Blocks => "█▓▒░ ",
+            Self::Digits => "8096543271",
+            Self::Binary => "01",
+        }
+    }
+}
+
+/// A named preset, the caller's own glyphs, or a ramp measured by `measure`; always densest first.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RampSpec {
+    Preset(RampPreset),
+    Custom(String),
+    Measured(MeasuredRamp),
+}
+
+impl Default for RampSpec {
+    fn default() -> Self {
+        Self::Preset(RampPreset::default())
+    }
+}
+
+/// Glyphs densest first, each with the share of its cell it inks in the measured font.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeasuredRamp {
+    pub glyphs: String,
+    pub coverage: Vec<f32>,
+}
+
+pub struct Ramp {
+    glyphs: Vec<char>,
+    /// Gray value each glyph stands for when measured; hand-made ramps are taken as evenly spaced.
+    levels: Option<Vec<f32>>,
+}
+
+impl Ramp {
+    pub fn new(spec: &RampSpec) -> Result<Self, String> {
+        let (glyphs, levels): (Vec<char>, _) = match spec {
+            RampSpec::Preset(preset) => (preset.glyphs().chars().collect(), None),
+            RampSpec::Custom(custom) => (custom.chars().collect(), None),
+            RampSpec::Measured(measured) => (measured.glyphs.chars().collect(), Some(gray_levels(&measured.coverage))),
+        };
+        if glyphs.len() < 2 { return Err("A ramp needs at least two characters".into()); }
+        if glyphs.len() > MAX_GLYPHS { return Err(format!("A ramp can have at most {} characters", MAX_GLYPHS)); }
+        if glyphs.iter().any(|c| c.is_control()) { return Err("A ramp cannot contain control characters".into()); }
+        if levels.as_ref().is_some_and(|levels| levels.len() != glyphs.len()) {
+            return Err("A measured ramp needs one coverage value per character".into());
+        }
+        Ok(Self { glyphs, levels })
+    }
+
+    pub fn glyphs(&self) -> &[char] {
+        &self.glyphs
+    }
+
+    /// Map an adjusted gray value (0 black to 255 white) to the glyph whose level is nearest, the same
+    /// snap dithering makes. Dark gets the dense glyphs, as dark ink on a light page; `invert` gives
+    /// bright the dense glyphs for light text on a dark terminal.
+    pub fn glyph(&self, value: f32, invert: bool) -> char {
+        self.glyphs[nearest(&self.levels(invert), value.clamp(0.0, 255.0))]
+    }
+
+    /// The adjusted gray value each glyph stands for, in glyph order; dithering snaps to these.
+    pub fn levels(&self, invert: bool) -> Vec<f32> {
+        let last = self.glyphs.len() - 1;
+        (0..self.glyphs.len()).map(|i| {
+            let level = match &self.levels {
+                Some(levels) => levels[i],
+                None => i as f32 * 255.0 / last as f32,
+            };
+            if invert { 255.0 - level } else { level }
+        }).collect()
+    }
+}
+
+/// Index of the level closest to `value`.
+pub fn nearest(levels: &[f32], value: f32) -> usize {
+    levels.iter().enumerate()
+        .min_by(|(_, a), (_, b)| (*a - value).abs().total_cmp(&(*b - value).abs()))
+        .map_or(0, |(i, _)| i)
+}
+
+/// Stretch coverage so the densest glyph stands for black and the emptiest for white.
+fn gray_levels(coverage: &[f32]) -> Vec<f32> {
+    let max = coverage.iter().copied().fold(f32::MIN, f32::max);
+    let min = coverage.iter().copied().fold(f32::MAX, f32::min);
+    let span = (max - min).max(f32::EPSILON);
+    coverage.iter().map(|&c| (max - c) / span * 255.0).collect()
+}
+
+/// Rasterize `candidates` (printable ASCII when `None`) in `font` and keep `levels` glyphs whose
+/// ink is as evenly spaced as the set allows, densest first.
+pub fn measure(font: &FontVec, candidates: Option<&str>, levels: usize) -> Result<MeasuredRamp, String> {
+    if levels < 2 { return Err("A ramp needs at least two characters".into()); }
+    let mut candidates: Vec<char> = candidates.unwrap_or(DEFAULT_CANDIDATES).chars().filter(|c| !c.is_control()).collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+    let mut measured: Vec<(char, f32)> = candidates.iter().copied()
+        .zip(font::coverage(font, &candidates))
+        .filter_map(|(c, coverage)| Some((c, coverage?)))
+        .collect();
+    measured.sort_by(|a, b| b.1.total_cmp(&a.1));
+    measured.dedup_by(|a, b| (a.1 - b.1).abs() < 1e-4);
+    if measured.len() < 2 { return Err("The font draws fewer than two distinct candidate characters".into()); }
+
+    // Walk evenly spaced targets from densest to emptiest, taking the nearest glyph not yet used.
+    let (max, min) = (measured[0].1, measured[measured.len() - 1].1);
+    let steps = levels.min(measured.len());
+    let mut picked: Vec<(char, f32)> = Vec::with_capacity(steps);
+    let mut next = 0;
+    for step in 0..steps {
+        let target = max - (max - min) * step as f32 / (steps - 1) as f32;
+        let remaining = steps - step;
+        let last_allowed = measured.len() - remaining;
+        let best = (next..=last_allowed)
+            .min_by(|&a, &b| (measured[a].1 - target).abs().total_cmp(&(measured[b].1 - target).abs()))
+            .unwrap_or(next);
+        picked.push(measured[best]);
+        next = best + 1;
+    }
+    Ok(MeasuredRamp {
+        glyphs: picked.iter().map(|&(c, _)| c).collect(),
+        coverage: picked.iter().map(|&(_, coverage)| coverage).collect(),
+    })
+}
@@ -0,0 +1,610 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Turn cached luma tensors into UTF-8 text, one frame after another, optionally colored from the RGB tensors.
+
+use crate::ansi::{AnsiWriter, Rgb};
+use crate::cache::{Resample, SubCells};
+use crate::dither::Dither;
+use crate::edges;
+use crate::palette::Palette;
+use crate::ramp::{self, Ramp, RampSpec};
+use crate::shape::{self, GlyphAtlas};
+use ab_glyph::FontVec;
+use ndarray::{s, Array2, Array3, ArrayView2, Axis, CowArray, Ix2};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Sub-cell samples in one shape-matching patch.
+const PATCH_LEN: usize = (shape::GRID.cols * shape::GRID.rows) as usize;
+
+/// How each character cell is chosen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RenderMode {
+    /// Look the cell's brightness up in the ramp.
+    #[default]
+    Ramp,
+    /// Pick the ramp character whose drawn shape best matches the cell's detail; needs a glyph font.
+    Shape,
+    /// Two stacked pixels per cell as `▀`/`▄` with ANSI foreground and background colors.
+    HalfBlock,
+    /// A 2x4 dot pattern per cell from the U+2800 braille block.
+    Braille,
+    /// The best two-color split of a 2x3 patch, drawn with Unicode 13 sextant blocks.
+    Sextant,
+    /// The best two-color split of a 2x2 patch, drawn with quadrant blocks.
+    Quadrant,
+}
+
+impl RenderMode {
+    /// The sub-cell samples this mode reads from the cache.
+    pub fn grid(self) -> SubCells {
+        match self {
+            Self::Ramp => SubCells::ONE,
+            Self::Shape => shape::GRID,
+            Self::HalfBlock => SubCells { cols: 1, rows: 2 },
+            Self::Braille => SubCells { cols: 2, rows: 4 },
+            Self::Sextant => SubCells { cols: 2, rows: 3 },
+            Self::Quadrant => SubCells { cols: 2, rows: 2 },
+        }
+    }
+
+    /// Whether cells are plain characters that the edge pass can stand in for.
+    fn draws_characters(self) -> bool {
+        matches!(self, Self::Ramp | Self::Shape)
+    }
+
+    /// Whether cells are ANSI blocks that carry their own colors, rather than characters to be tinted.
+    fn paints_blocks(self) -> bool {
+        matches!(self, Self::HalfBlock | Self::Sextant | Self::Quadrant)
+    }
+}
+
+/// Everything the frontend controls about how a document becomes text.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RenderSettings {
+    pub width: u32,
+    pub filter: Resample,
+    pub brightness: i32,
+    pub contrast: f32,
+    pub ramp: RampSpec,
+    /// Give bright cells the dense glyphs, for light text on a dark terminal; the block modes show a negative.
+    pub invert: bool,
+    pub mode: RenderMode,
+    /// Outline cells whose gradient exceeds this fraction of the strongest possible; `None` turns it off.
+    /// Only character modes are outlined.
+    pub edge_threshold: Option<f32>,
+    /// Gray level below which a pixel becomes a dot in the dot modes (above it when inverted).
+    pub threshold: u8,
+    /// How the ramp and dot modes pick glyphs, and how colors are snapped to an indexed palette.
+    pub dither: Dither,
+    /// Keep error diffusion within fixed tiles so still areas of an animation don't crawl.
+    pub stable_dither: bool,
+    /// Keep the source colors: characters are tinted with their cell's average, blocks are filled with it.
+    pub color: bool,
+    /// The colors ANSI output is limited to.
+    pub palette: Palette,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            width: 100,
+            filter: Resample::default(),
+            brightness: 0,
+            contrast: 1.0,
+            ramp: RampSpec::default(),
+            invert: false,
+            mode: RenderMode::default(),
+            edge_threshold: None,
+            threshold: 128,
+            dither: Dither::default(),
+            stable_dither: false,
+            color: false,
+            palette: Palette::default(),
+        }
+    }
+}
+
+/// Frame `i` is `data[offsets[i]..offsets[i + 1]]`; glyphs can be several bytes, so frames differ in length.
+pub struct Rendered {
+    pub data: Vec<u8>,
+    pub offsets: Vec<usize>,
+}
+
+/// Apply brightness, then contrast around mid-gray; shared with the source preview.
+pub fn adjust(gray: u8, brightness: i32, contrast: f32) -> f32 {
+    let mut val = gray as f32 + brightness as f32;
+    if (contrast - 1.0).abs() > 0.01 { val = (val - 128.0) * contrast + 128.0; }
+    val
+}
+
+/// `colors` is the matching RGB tensor, wanted when `settings.color` is on; `font` is only needed for `RenderMode::Shape`.
+pub fn render(
+    tensor: &Array3<u8>,
+    colors: Option<&Array3<u8>>,
+    frames: &[usize],
+    settings: &RenderSettings,
+    font: Option<&FontVec>
+) -> Result<Rendered, String> {
+    let ramp = Ramp::new(&settings.ramp)?;
+    let grid = settings.mode.grid();
+    let colors = colors.filter(|_| settings.color);
+    let blocks = settings.mode.paints_blocks();
+    let dither_palette = settings.dither != Dither::None && settings.palette != Palette::TrueColor;
+    let color_pass = (colors.is_some() || (blocks && dither_palette)).then(|| ColorPass {
+        tensor: colors,
+        // Characters keep their source hue even when inverted; only the glyph choice flips.
+        levels: if blocks { color_levels(settings) } else {
+            (0..=255u8).map(|value| adjust(value, settings.brightness, settings.contrast).clamp(0.0, 255.0) as u8).collect()
+        },
+        palette: settings.palette,
+        dither: settings.dither,
+        stable: settings.stable_dither,
+    });
+    let source = Source {
+        luma: tensor,
+        colors: color_pass.as_ref(),
+        tint: colors.filter(|_| !blocks).map(|_| settings.palette),
+    };
+    let edges = settings.edge_threshold.filter(|_| settings.mode.draws_characters()).map(|threshold| EdgePass {
+        threshold,
+        invert: settings.invert,
+        levels: (0..=255u8).map(|gray| adjust(gray, settings.brightness, settings.contrast).clamp(0.0, 255.0)).collect(),
+    });
+    let edges = edges.as_ref();
+    match settings.mode {
+        RenderMode::Ramp if settings.dither != Dither::None => {
+            let dithered = DitheredRamp {
+                values: (0..=255u8).map(|gray| adjust(gray, settings.brightness, settings.contrast).clamp(0.0, 255.0)).collect(),
+                levels: ramp.levels(settings.invert),
+                glyphs: ramp.glyphs().iter().map(|glyph| glyph.to_string().into_bytes()).collect(),
+                dither: settings.dither,
+                stable: settings.stable_dither,
+            };
+            Ok(render_cells(source, frames, grid, edges, || &dithered))
+        }
+        RenderMode::Ramp => {
+            let lut: Vec<Vec<u8>> = (0..=255u8).map(|gray| {
+                let glyph = ramp.glyph(adjust(gray, settings.brightness, settings.contrast), settings.invert);
+                glyph.to_string().into_bytes()
+            }).collect();
+            Ok(render_cells(source, frames, grid, edges, || {
+                |patch: &[u8], out: &mut Vec<u8>| out.extend_from_slice(&lut[patch[0] as usize])
+            }))
+        }
+        RenderMode::Shape => {
+            let font = font.ok_or("Choose a glyph font for shape matching")?;
+            let atlas = GlyphAtlas::build(font, ramp.glyphs())?;
+            // Ink wanted per gray level: dense for black, as in the ramp, unless inverted.
+            let ink: Vec<f32> = (0..=255u8).map(|gray| {
+                let dark = 1.0 - adjust(gray, settings.brightness, settings.contrast).clamp(0.0, 255.0) / 255.0;
+                if settings.invert { 1.0 - dark } else { dark }
+            }).collect();
+            Ok(render_cells(source, frames, grid, edges, || {
+                |patch: &[u8], out: &mut Vec<u8>| {
+                    let mut target = [0.0f32; PATCH_LEN];
+                    for (t, &gray) in target.iter_mut().zip(patch) { *t = ink[gray as usize]; }
+                    let mut utf8 = [0u8; 4];
+                    out.extend_from_slice(atlas.best(&target[..patch.len()]).encode_utf8(&mut utf8).as_bytes());
+                }
+            }))
+        }
+        RenderMode::HalfBlock => {
+            let levels = color_levels(settings);
+            Ok(render_cells(source, frames, grid, edges, || HalfBlocks { levels: &levels, ansi: AnsiWriter::new(settings.palette) }))
+        }
+        RenderMode::Sextant | RenderMode::Quadrant => {
+            let levels = color_levels(settings);
+            let glyph = if settings.mode == RenderMode::Sextant { sextant } else { quadrant };
+            Ok(render_cells(source, frames, grid, edges, || Mosaic { levels: &levels, glyph, ansi: AnsiWriter::new(settings.palette) }))
+        }
+        RenderMode::Braille => {
+            let dots = Dots::new(settings, braille);
+            Ok(render_cells(source, frames, grid, edges, || &dots))
+        }
+    }
+}
+
+/// Displayed value per cached level and channel for the block modes; inverting gives a negative.
+fn color_levels(settings: &RenderSettings) -> Vec<u8> {
+    (0..=255u8).map(|gray| {
+        let level = adjust(gray, settings.brightness, settings.contrast).clamp(0.0, 255.0) as u8;
+        if settings.invert { 255 - level } else { level }
+    }).collect()
+}
+
+/// `pattern` sets bit `i` for sub-cell `i` of a row-major 2x3 patch.
+fn sextant(pattern: u32) -> char {
+    match pattern {
+        0 => ' ',
+        // Left and right halves already exist in the older block elements, so the sextant range skips them.
+        21 => '▌',
+        42 => '▐',
+        63 => '█',
+        p => char::from_u32(0x1FB00 + p - 1 - (p > 21) as u32 - (p > 42) as u32).unwrap_or(' '),
+    }
+}
+
+const QUADRANTS: [char; 16] = [' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█'];
+
+/// `pattern` sets bit `i` for sub-cell `i` of a row-major 2x2 patch.
+fn quadrant(pattern: u32) -> char {
+    QUADRANTS[pattern as usize & 15]
+}
+
+/// Braille dots are numbered down the left column, then the right, with the bottom row added last.
+const BRAILLE_BITS: [u32; 8] = [0x01, 0x08, 0x02, 0x10, 0x04, 0x20, 0x40, 0x80];
+
+/// `dots` is a row-major 2x4 patch of 0 or 1.
+fn braille(dots: &[u8]) -> char {
+    let bits = dots.iter().zip(BRAILLE_BITS).filter(|(&dot, _)| dot != 0).fold(0, |bits, (_, bit)| bits | bit);
+    char::from_u32(0x2800 + bits).unwrap_or(' ')
+}
+
+/// One cell's samples, row-major; `rgb` holds three displayed values per sample, or nothing without a color pass.
+struct Patch {
+    luma: Vec<u8>,
+    rgb: Vec<u8>,
+}
+
+impl Patch {
+    /// Displayed color of sample `i`: from the color pass when there is one, else its gray through `levels`.
+    fn shade(&self, levels: &[u8], i: usize) -> Rgb {
+        match self.rgb.get(i * 3..i * 3 + 3) {
+            Some(rgb) => [rgb[0], rgb[1], rgb[2]],
+            None => [levels[self.luma[i] as usize]; 3],
+        }
+    }
+
+    /// The average displayed color.
+    fn mean(&self) -> Rgb {
+        let samples = (self.rgb.len() / 3).max(1) as u32;
+        let mut sum = [0u32; 3];
+        for rgb in self.rgb.chunks_exact(3) {
+            for c in 0..3 { sum[c] += rgb[c] as u32; }
+        }
+        sum.map(|total| ((total + samples / 2) / samples) as u8)
+    }
+}
+
+/// Turns one cell's samples into output; a fresh writer starts every frame so it can carry state along rows.
+trait CellWriter {
+    /// Rework the frame's samples before cells are cut from it; most writers read them as cached.
+    fn prepare<'a>(&self, plane: ArrayView2<'a, u8>) -> CowArray<'a, u8, Ix2> {
+        plane.into()
+    }
+
+    fn cell(&mut self, patch: &Patch, out: &mut Vec<u8>);
+
+    fn end_row(&mut self, out: &mut Vec<u8>) {
+        out.push(b'\n');
+    }
+}
+
+impl<F: FnMut(&[u8], &mut Vec<u8>)> CellWriter for F {
+    fn cell(&mut self, patch: &Patch, out: &mut Vec<u8>) {
+        self(&patch.luma, out)
+    }
+}
+
+struct HalfBlocks<'a> {
+    levels: &'a [u8],
+    ansi: AnsiWriter,
+}
+
+impl CellWriter for HalfBlocks<'_> {
+    fn cell(&mut self, patch: &Patch, out: &mut Vec<u8>) {
+        self.ansi.half_block(patch.shade(self.levels, 0), patch.shade(self.levels, 1), out);
+    }
+
+    fn end_row(&mut self, out: &mut Vec<u8>) {
+        self.ansi.end_row(out);
+    }
+}
+
+/// Splits each patch into the foreground and background groups whose grays leave the least squared error,
+/// then colors each group with its average.
+struct Mosaic<'a> {
+    levels: &'a [u8],
+    glyph: fn(u32) -> char,
+    ansi: AnsiWriter,
+}
+
+impl CellWriter for Mosaic<'_> {
+    fn cell(&mut self, patch: &Patch, out: &mut Vec<u8>) {
+        let values: Vec<f32> = patch.luma.iter().map(|&gray| self.levels[gray as usize] as f32).collect();
+        let full = (1u32 << values.len()) - 1;
+        // A pattern and its complement are the same split, so only try those with the first sub-cell unset.
+        let mut best = (f32::MAX, 0);
+        for pattern in (0..=full).filter(|p| p & 1 == 0) {
+            let (mut sums, mut counts) = ([0.0f32; 2], [0.0f32; 2]);
+            for (i, &v) in values.iter().enumerate() {
+                let side = ((pattern >> i) & 1) as usize;
+                sums[side] += v;
+                counts[side] += 1.0;
+            }
+            let means = [sums[0] / counts[0].max(1.0), sums[1] / counts[1].max(1.0)];
+            let error: f32 = values.iter().enumerate()
+                .map(|(i, &v)| (v - means[((pattern >> i) & 1) as usize]).powi(2))
+                .sum();
+            if error < best.0 { best = (error, pattern); }
+        }
+        let (_, pattern) = best;
+        let mean = |side: u32| {
+            let (mut sum, mut count) = ([0u32; 3], 0);
+            for i in (0..values.len()).filter(|&i| (pattern >> i) & 1 == side) {
+                let rgb = patch.shade(self.levels, i);
+                for c in 0..3 { sum[c] += rgb[c] as u32; }
+                count += 1;
+            }
+            sum.map(|total| ((total + count / 2) / count.max(1)) as u8)
+        };
+        let (fg, bg) = (mean(1), mean(0));
+        if pattern == 0 || fg == bg { return self.ansi.blank(bg, out); }
+        if self.ansi.is_set(bg, fg) {
+            self.ansi.block((self.glyph)(full ^ pattern), bg, fg, out);
+        } else {
+            self.ansi.block((self.glyph)(pattern), fg, bg, out);
+        }
+    }
+
+    fn end_row(&mut self, out: &mut Vec<u8>) {
+        self.ansi.end_row(out);
+    }
+}
+
+/// Thresholds (or dithers) every pixel to dot or blank up front, then packs each cell into a glyph.
+struct Dots {
+    /// Adjusted gray per cached level, flipped when inverted so a dot is always "below the cut".
+    values: Vec<f32>,
+    cut: f32,
+    dither: Dither,
+    stable: bool,
+    pack: fn(&[u8]) -> char,
+}
+
+impl Dots {
+    fn new(settings: &RenderSettings, pack: fn(&[u8]) -> char) -> Self {
+        let values = (0..=255u8).map(|gray| {
+            let level = adjust(gray, settings.brightness, settings.contrast).clamp(0.0, 255.0);
+            if settings.invert { 255.0 - level } else { level }
+        }).collect();
+        let cut = if settings.invert { 255.0 - settings.threshold as f32 } else { settings.threshold as f32 };
+        Self { values, cut, dither: settings.dither, stable: settings.stable_dither, pack }
+    }
+}
+
+impl CellWriter for &Dots {
+    fn prepare<'a>(&self, plane: ArrayView2<'a, u8>) -> CowArray<'a, u8, Ix2> {
+        let values = plane.mapv(|gray| [self.values[gray as usize]]);
+        self.dither.apply(values, 255.0, self.stable, |[v]| if v < self.cut { (1, [0.0]) } else { (0, [255.0]) }).into()
+    }
+
+    fn cell(&mut self, patch: &Patch, out: &mut Vec<u8>) {
+        let mut utf8 = [0u8; 4];
+        out.extend_from_slice((self.pack)(&patch.luma).encode_utf8(&mut utf8).as_bytes());
+    }
+}
+
+/// Dithers the frame's adjusted grays onto the ramp's levels up front, then writes each cell's glyph.
+struct DitheredRamp {
+    /// Adjusted gray per cached level.
+    values: Vec<f32>,
+    /// Gray each glyph stands for.
+    levels: Vec<f32>,
+    glyphs: Vec<Vec<u8>>,
+    dither: Dither,
+    stable: bool,
+}
+
+impl CellWriter for &DitheredRamp {
+    fn prepare<'a>(&self, plane: ArrayView2<'a, u8>) -> CowArray<'a, u8, Ix2> {
+        let values = plane.mapv(|gray| [self.values[gray as usize]]);
+        let spread = 255.0 / (self.levels.len() - 1) as f32;
+        self.dither.apply(values, spread, self.stable, |[v]| {
+            let nearest = ramp::nearest(&self.levels, v);
+            (nearest as u8, [self.levels[nearest]])
+        }).into()
+    }
+
+    fn cell(&mut self, patch: &Patch, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.glyphs[patch.luma[0] as usize]);
+    }
+}
+
+/// Works out the color of every sample of a frame, as displayed.
+struct ColorPass<'a> {
+    /// Source colors; without them each sample shows its gray.
+    tensor: Option<&'a Array3<u8>>,
+    /// Displayed value per cached level and channel.
+    levels: Vec<u8>,
+    palette: Palette,
+    dither: Dither,
+    stable: bool,
+}
+
+impl ColorPass<'_> {
+    /// Three values per sample of `luma`'s frame, dithered onto the palette when it is indexed.
+    fn plane(&self, luma: ArrayView2<u8>, f_idx: usize) -> Array2<u8> {
+        let (h, w) = luma.dim();
+        let colors = self.tensor.map(|tensor| tensor.index_axis(Axis(0), f_idx));
+        let sample = |y: usize, x: usize| -> Rgb {
+            match &colors {
+                Some(colors) => [0, 1, 2].map(|c| self.levels[colors[[y, x * 3 + c]] as usize]),
+                None => [self.levels[luma[[y, x]] as usize]; 3],
+            }
+        };
+        if self.dither == Dither::None || self.palette == Palette::TrueColor {
+            return Array2::from_shape_fn((h, w * 3), |(y, x)| sample(y, x / 3)[x % 3]);
+        }
+        let values = Array2::from_shape_fn((h, w), |(y, x)| sample(y, x).map(|c| c as f32));
+        let snapped = self.dither.apply(values, self.palette.spread(), self.stable, |rgb| {
+            let color = self.palette.nearest(rgb.map(|c| c.clamp(0.0, 255.0).round() as u8));
+            (color, color.map(|c| c as f32))
+        });
+        Array2::from_shape_fn((h, w * 3), |(y, x)| snapped[[y, x / 3]][x % 3])
+    }
+}
+
+/// What a render reads: the gray tensor always, colors when the output shows them,
+/// and the palette characters are tinted in when they keep their source colors.
+#[derive(Clone, Copy)]
+struct Source<'a> {
+    luma: &'a Array3<u8>,
+    colors: Option<&'a ColorPass<'a>>,
+    tint: Option<Palette>,
+}
+
+struct EdgePass {
+    threshold: f32,
+    invert: bool,
+    /// Adjusted brightness per gray level, so contrast sharpens the outline too.
+    levels: Vec<f32>,
+}
+
+impl EdgePass {
+    fn detect(&self, plane: ArrayView2<u8>, grid: SubCells) -> Array2<Option<u8>> {
+        let (sx, sy) = (grid.cols as usize, grid.rows as usize);
+        let cells = Array2::from_shape_fn((plane.dim().0 / sy, plane.dim().1 / sx), |(y, x)| {
+            let patch = plane.slice(s![y * sy..(y + 1) * sy, x * sx..(x + 1) * sx]);
+            patch.iter().map(|&gray| self.levels[gray as usize]).sum::<f32>() / (sx * sy) as f32
+        });
+        edges::directions(cells.view(), self.threshold, self.invert)
+    }
+}
+
+/// Walk each requested frame cell by cell, handing the frame's writer the cell's `grid` samples
+/// row-major, except where the edge pass has already chosen a character. With a tint, every character
+/// is preceded by its cell's color.
+fn render_cells<W: CellWriter>(
+    source: Source,
+    frames: &[usize],
+    grid: SubCells,
+    edges: Option<&EdgePass>,
+    new_writer: impl Fn() -> W + Sync
+) -> Rendered {
+    let (sx, sy) = (grid.cols as usize, grid.rows as usize);
+    let texts: Vec<Vec<u8>> = frames.par_iter().map(|&f_idx| {
+        let plane = source.luma.index_axis(Axis(0), f_idx);
+        let colors = source.colors.map(|pass| pass.plane(plane, f_idx));
+        let (h, w) = (plane.dim().0 / sy, plane.dim().1 / sx);
+        let outline = edges.map(|pass| pass.detect(plane, grid));
+        let mut writer = new_writer();
+        let mut tint = source.tint.map(AnsiWriter::new);
+        let plane = writer.prepare(plane);
+        let mut text = Vec::with_capacity((w + 1) * h);
+        let mut patch = Patch { luma: Vec::with_capacity(sx * sy), rgb: Vec::with_capacity(sx * sy * 3) };
+        let mut glyph = Vec::with_capacity(4);
+        for y in 0..h {
+            for x in 0..w {
+                patch.luma.clear();
+                patch.rgb.clear();
+                for row in y * sy..(y + 1) * sy {
+                    for col in x * sx..(x + 1) * sx {
+                        patch.luma.push(plane[[row, col]]);
+                        if let Some(colors) = &colors {
+                            patch.rgb.extend((0..3).map(|c| colors[[row, col * 3 + c]]));
+                        }
+                    }
+                }
+                glyph.clear();
+                match outline.as_ref().and_then(|outline| outline[[y, x]]) {
+                    Some(edge) => glyph.push(edge),
+                    None => writer.cell(&patch, &mut glyph),
+                }
+                match &mut tint {
+                    Some(ansi) => ansi.glyph(&glyph, patch.mean(), &mut text),
+                    None => text.extend_from_slice(&glyph),
+                }
+            }
+            match &mut tint {
+                Some(ansi) => ansi.end_row(&mut text),
+                None => writer.end_row(&mut text),
+            }
+        }
+        text
+    }).collect();
+
+    let mut offsets = Vec::with_capacity(texts.len() + 1);
+    offsets.push(0);
+    for text in &texts { offsets.push(offsets[offsets.len() - 1] + text.len()); }
+    Rendered { data: texts.concat(), offsets }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A row-major 2x4 patch with the dots at `(col, row)` set.
+    fn dots(set: &[(usize, usize)]) -> [u8; 8] {
+        let mut patch = [0; 8];
+        for &(col, row) in set { patch[row * 2 + col] = 1; }
+        patch
+    }
+
+    #[test]
+    fn braille_numbers_dots_by_column() {
+        assert_eq!(braille(&[0; 8]), '\u{2800}');
+        assert_eq!(braille(&[1; 8]), '⣿');
+        assert_eq!(braille(&dots(&[(0, 0)])), '⠁');
+        assert_eq!(braille(&dots(&[(0, 0), (0, 1), (0, 2), (0, 3)])), '⡇');
+        assert_eq!(braille(&dots(&[(1, 0), (1, 1), (1, 2), (1, 3)])), '⢸');
+        assert_eq!(braille(&dots(&[(0, 3), (1, 3)])), '⣀');
+    }
+
+    #[test]
+    fn sextant_skips_the_half_blocks() {
+        assert_eq!(sextant(0), ' ');
+        assert_eq!(sextant(1), '🬀');
+        assert_eq!(sextant(3), '🬂');
+        assert_eq!(sextant(20), '🬓');
+        assert_eq!(sextant(21), '▌');
+        assert_eq!(sextant(22), '🬔');
+        assert_eq!(sextant(41), '🬧');
+        assert_eq!(sextant(42), '▐');
+        assert_eq!(sextant(43), '🬨');
+        assert_eq!(sextant(62), '🬻');
+        assert_eq!(sextant(63), '█');
+    }
+
+    #[test]
+    fn every_sextant_is_distinct() {
+        let mut glyphs: Vec<char> = (0..64).map(sextant).collect();
+        glyphs.sort_unstable();
+        glyphs.dedup();
+        assert_eq!(glyphs.len(), 64);
+    }
+
+    #[test]
+    fn quadrant_bits_are_row_major() {
+        assert_eq!(quadrant(0), ' ');
+        assert_eq!(quadrant(0b0001), '▘');
+        assert_eq!(quadrant(0b0010), '▝');
+        assert_eq!(quadrant(0b0100), '▖');
+        assert_eq!(quadrant(0b1000), '▗');
+        assert_eq!(quadrant(0b0110), '▞');
+        assert_eq!(quadrant(0b1101), '▙');
+        assert_eq!(quadrant(0b1111), '█');
+    }
+
+    /// Mean digit drawn for a 40x40 field of `gray` with the ramp `0123456789`.
+    fn mean_digit(gray: u8, dither: Dither) -> f32 {
+        let settings = RenderSettings { ramp: RampSpec::Custom("0123456789".into()), dither, ..RenderSettings::default() };
+        let tensor = Array3::from_elem((1, 40, 40), gray);
+        let rendered = render(&tensor, None, &[0], &settings, None).unwrap();
+        let digits: Vec<u32> = String::from_utf8(rendered.data).unwrap().chars().filter_map(|c| c.to_digit(10)).collect();
+        digits.iter().sum::<u32>() as f32 / digits.len() as f32
+    }
+
+    #[test]
+    fn dithering_keeps_the_undithered_mean() {
+        // 135 lies three quarters of the way from level 4 to level 5, so snapping alone draws 5.
+        assert_eq!(mean_digit(135, Dither::None), 5.0);
+        for dither in [Dither::FloydSteinberg, Dither::Atkinson, Dither::JarvisJudiceNinke, Dither::Bayer4, Dither::Bayer8] {
+            let mean = mean_digit(135, dither);
+            assert_eq!(mean.round(), 5.0, "{:?} averages {}", dither, mean);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Pick the glyph whose rasterized shape best matches each cell's sub-cell patch.
+
+use crate::cache::SubCells;
+use crate::font;
+use ab_glyph::FontVec;
+
+/// Patch sampled per character; matches the default two-to-one cell shape.
+pub const GRID: SubCells = SubCells { cols: 4, rows: 8 };
+
+pub struct GlyphAtlas {
+    glyphs: Vec<char>,
+    /// Ink per sub-cell for each glyph, stretched so the densest glyph reads as solid.
+    patches: Vec<Vec<f32>>,
+}
+
+impl GlyphAtlas {
+    /// Rasterize `glyphs` at `GRID` resolution, skipping any the font cannot draw.
+    pub fn build(font: &FontVec, glyphs: &[char]) -> Result<Self, String> {
+        let (cols, rows) = (GRID.cols as usize, GRID.rows as usize);
+        let (glyphs, mut patches): (Vec<char>, Vec<Vec<f32>>) = glyphs.iter()
+            .filter_map(|&c| Some((c, font::rasterize(font, c, cols, rows)?)))
+            .unzip();
+        if glyphs.len() < 2 { return Err("The glyph font draws fewer than two of the ramp's characters".into()); }
+        let densest = patches.iter().map(|patch| patch.iter().sum::<f32>() / patch.len() as f32).fold(0.0, f32::max);
+        if densest > 0.0 {
+            for value in patches.iter_mut().flatten() { *value = (*value / densest).min(1.0); }
+        }
+        Ok(Self { glyphs, patches })
+    }
+
+    /// The glyph nearest `ink` (one 0..1 target per sub-cell, row-major) by squared error.
+    pub fn best(&self, ink: &[f32]) -> char {
+        let mut best = (f32::MAX, self.glyphs[0]);
+        for (&glyph, patch) in self.glyphs.iter().zip(&self.patches) {
+            let error: f32 = patch.iter().zip(ink).map(|(a, b)| (a - b) * (a - b)).sum();
+            if error < best.0 { best = (error, glyph); }
+        }
+        best.1
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Write frames as SVG: one `<text>` per row with a `<tspan>` per run of equal colors, behind it a
+//! `<rect>` per run of equal backgrounds. Animations get a group per frame that SMIL `<animate>`
+//! shows for exactly that frame's delay; viewers without SMIL show the first frame.
+
+use crate::ansi;
+use crate::export::{ExportOptions, FrameSink, Header};
+use crate::html::{escape, hex};
+use crate::media::LoopCount;
+use std::io::{self, Write};
+
+/// The preview's font; any monospace family works since every row is stretched to the grid.
+const DEFAULT_FONT: &str = "'Fira Code', Consolas, monospace";
+const FONT_SIZE: f32 = 10.0;
+/// A typical monospace advance; `textLength` pins each row to it whatever the font.
+const CELL_WIDTH: f32 = FONT_SIZE * 0.6;
+/// Rows touch, as in the preview's `line-height: 1`.
+const CELL_HEIGHT: f32 = FONT_SIZE;
+/// Distance from a row's top to its baseline.
+const BASELINE: f32 = FONT_SIZE * 0.8;
+
+pub struct SvgSink<W: Write> {
+    out: W,
+    columns: usize,
+    /// Frame `i` shows from `starts_ms[i]` until `starts_ms[i + 1]`; the last entry is the total.
+    starts_ms: Vec<u64>,
+    loop_count: LoopCount,
+}
+
+impl<W: Write> SvgSink<W> {
+    pub fn open(header: &Header, options: &ExportOptions, mut out: W) -> io::Result<Self> {
+        let (width, height) = (header.columns as f32 * CELL_WIDTH, header.rows as f32 * CELL_HEIGHT);
+        let font = escape(options.font_family.as_deref().unwrap_or(DEFAULT_FONT));
+        writeln!(out, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#, w = width, h = height)?;
+        writeln!(out, "<title>{}</title>", escape(header.source))?;
+        writeln!(out, r##"<rect width="100%" height="100%" fill="#000"/>"##)?;
+        writeln!(out, r##"<g font-family="{}" font-size="{}" fill="#b8b1b1" xml:space="preserve">"##, font, FONT_SIZE)?;
+        let mut starts_ms = vec![0u64];
+        for i in 0..header.frame_count {
+            starts_ms.push(starts_ms[i] + header.timing.delay_ms(i) as u64);
+        }
+        Ok(Self { out, columns: header.columns, starts_ms, loop_count: header.timing.loop_count })
+    }
+
+    /// Discrete `visibility` keyframes over one playthrough. The first frame ends hidden and the last
+    /// ends visible, so an animation that stops freezes on its last frame.
+    fn animate(&mut self, index: usize) -> io::Result<()> {
+        let total = self.starts_ms[self.starts_ms.len() - 1].max(1) as f64;
+        let (start, end) = (self.starts_ms[index] as f64 / total, self.starts_ms[index + 1] as f64 / total);
+        let last = index + 2 == self.starts_ms.len();
+        let (values, key_times) = match (index, last) {
+            (0, _) => ("visible;hidden", format!("0;{:.6}", end)),
+            (_, true) => ("hidden;visible", format!("0;{:.6}", start)),
+            _ => ("hidden;visible;hidden", format!("0;{:.6};{:.6}", start, end)),
+        };
+        let repeat = match self.loop_count {
+            LoopCount::Forever => "indefinite".to_string(),
+            LoopCount::Times(n) => n.max(1).to_string(),
+        };
+        writeln!(
+            self.out,
+            r#"<animate attributeName="visibility" calcMode="discrete" values="{}" keyTimes="{}" dur="{}ms" repeatCount="{}" fill="freeze"/>"#,
+            values, key_times, total, repeat
+        )
+    }
+}
+
+impl<W: Write> FrameSink for SvgSink<W> {
+    fn frame(&mut self, index: usize, _delay_ms: u32, text: &[u8]) -> io::Result<()> {
+        let animated = self.starts_ms.len() > 2;
+        if index > 0 { writeln!(self.out, r#"<g visibility="hidden">"#)?; } else { writeln!(self.out, "<g>")?; }
+        if animated { self.animate(index)?; }
+
+        let rows = ansi::styled_rows(&String::from_utf8_lossy(text));
+        for (r, runs) in rows.iter().enumerate() {
+            let top = r as f32 * CELL_HEIGHT;
+            let mut column = 0;
+            for run in runs {
+                let cells = run.text.chars().count();
+                if let Some(bg) = run.style.bg {
+                    writeln!(
+                        self.out,
+                        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                        column as f32 * CELL_WIDTH, top, cells as f32 * CELL_WIDTH, CELL_HEIGHT, hex(bg)
+                    )?;
+                }
+                column += cells;
+            }
+            if runs.iter().all(|run| run.text.trim().is_empty()) { continue; }
+
+            write!(
+                self.out,
+                r#"<text y="{}" textLength="{}" lengthAdjust="spacingAndGlyphs">"#,
+                top + BASELINE, self.columns as f32 * CELL_WIDTH
+            )?;
+            for run in runs {
+                match run.style.fg {
+                    Some(fg) => write!(self.out, r#"<tspan fill="{}">{}</tspan>"#, hex(fg), escape(&run.text))?,
+                    None => write!(self.out, "{}", escape(&run.text))?,
+                }
+            }
+            writeln!(self.out, "</text>")?;
+        }
+        writeln!(self.out, "</g>")
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        writeln!(self.out, "</g>")?;
+        writeln!(self.out, "</svg>")?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media::Timing;
+    use crate::render::RenderSettings;
+
+    fn svg(delays_ms: Vec<u32>, loop_count: LoopCount) -> String {
+        let frame_count = delays_ms.len();
+        let timing = Timing { delays_ms, loop_count };
+        let settings = RenderSettings::default();
+        let header = Header { columns: 2, rows: 1, frame_count, timing: &timing, source: "clip.gif", settings: &settings };
+        let mut out = Vec::new();
+        let mut sink = SvgSink::open(&header, &ExportOptions::default(), &mut out).unwrap();
+        for i in 0..frame_count { sink.frame(i, timing.delay_ms(i), b"a<\n").unwrap(); }
+        sink.finish().unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn attribute<'a>(tag: &'a str, name: &str) -> &'a str {
+        let start = tag.find(&format!(" {}=\"", name)).unwrap() + name.len() + 3;
+        &tag[start..start + tag[start..].find('"').unwrap()]
+    }
+
+    #[test]
+    fn key_times_cover_every_frame() {
+        let svg = svg(vec![100, 200, 100], LoopCount::Times(2));
+        let animations: Vec<&str> = svg.lines().filter(|line| line.starts_with("<animate ")).collect();
+        let windows: Vec<(&str, &str)> = animations.iter().map(|a| (attribute(a, "values"), attribute(a, "keyTimes"))).collect();
+        assert_eq!(windows, [
+            ("visible;hidden", "0;0.250000"),
+            ("hidden;visible;hidden", "0;0.250000;0.750000"),
+            ("hidden;visible", "0;0.750000"),
+        ]);
+        for animation in animations {
+            assert_eq!((attribute(animation, "dur"), attribute(animation, "repeatCount")), ("400ms", "2"));
+        }
+    }
+
+    #[test]
+    fn stills_are_not_animated() {
+        let svg = svg(vec![100], LoopCount::Forever);
+        assert!(!svg.contains("<animate"));
+        assert!(svg.contains(r#"lengthAdjust="spacingAndGlyphs">a&lt;</text>"#));
+    }
+}
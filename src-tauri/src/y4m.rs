@@ -0,0 +1,323 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+//! Read YUV4MPEG2 streams, e.g. `ffmpeg -i clip.mp4 -f yuv4mpegpipe clip.y4m`, into RGB or, for mono streams, luma planes.
+
+use crate::media::{DecodedMedia, LoopCount, SourceFrames, Timing, MAX_SOURCE_BYTES};
+use crate::progress::LoadMonitor;
+use image::{GrayImage, RgbImage};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+
+const MAGIC: &[u8] = b"YUV4MPEG2 ";
+
+pub fn sniff(path: &str) -> Result<bool, String> {
+    let mut head = [0u8; MAGIC.len()];
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    match file.read_exact(&mut head) {
+        Ok(()) => Ok(head == MAGIC),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+struct Header {
+    width: usize,
+    height: usize,
+    fps: (u64, u64),
+    bytes_per_sample: usize,
+    bit_depth: u32,
+    /// Horizontal and vertical chroma subsampling, or `None` for mono streams.
+    subsampling: Option<(usize, usize)>,
+    /// An alpha plane follows the chroma planes.
+    alpha: bool,
+    full_range: bool,
+}
+
+impl Header {
+    /// Samples in each of the Cb and Cr planes.
+    fn chroma_samples(&self) -> usize {
+        self.subsampling.map_or(0, |(sx, sy)| self.width.div_ceil(sx) * self.height.div_ceil(sy))
+    }
+}
+
+/// Mono streams load as gray; everything else is converted to RGB so color output keeps the source colors.
+pub fn decode(path: &str, monitor: &LoadMonitor) -> Result<DecodedMedia, String> {
+    decode_within(path, monitor, MAX_SOURCE_BYTES)
+}
+
+/// Decode at most `max_bytes` of frames, failing on longer clips.
+fn decode_within(path: &str, monitor: &LoadMonitor, max_bytes: usize) -> Result<DecodedMedia, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(monitor.track(file)?);
+    let header = read_header(&mut reader)?;
+    let range_lut = range_lut(&header);
+
+    let pixels = header.width * header.height;
+    let max_frames = (max_bytes / (pixels * if header.subsampling.is_some() { 3 } else { 1 })).max(1);
+    let mut luma = vec![0u8; pixels * header.bytes_per_sample];
+    let mut chroma = vec![0u8; 2 * header.chroma_samples() * header.bytes_per_sample];
+    let mut alpha = vec![0u8; if header.alpha { pixels * header.bytes_per_sample } else { 0 }];
+    let mut line = Vec::new();
+    let (mut gray, mut rgb) = (Vec::new(), Vec::new());
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).map_err(|e| e.to_string())? == 0 { break; }
+        if !line.starts_with(b"FRAME") { return Err("Corrupt Y4M stream: expected FRAME".into()); }
+        if gray.len() + rgb.len() == max_frames {
+            return Err(format!(
+                "Y4M clip is longer than {} frames at {}x{}; trim it or scale it down, e.g. with ffmpeg -vf scale=480:-2",
+                max_frames, header.width, header.height
+            ));
+        }
+        for plane in [&mut luma, &mut chroma, &mut alpha] {
+            reader.read_exact(plane).map_err(|e| match e.kind() {
+                ErrorKind::UnexpectedEof => "Truncated Y4M frame".to_string(),
+                _ => e.to_string(),
+            })?;
+        }
+        let y: Vec<u8> = eight_bit(&luma, &header).iter().map(|&y| range_lut[y as usize]).collect();
+        match header.subsampling {
+            None => gray.push(GrayImage::from_raw(header.width as u32, header.height as u32, y)
+                .expect("luma plane matches header dimensions")),
+            Some(_) => rgb.push(to_rgb(&header, &y, &eight_bit(&chroma, &header), &eight_bit(&alpha, &header))),
+        }
+        monitor.decoded(gray.len() + rgb.len())?;
+    }
+
+    // Spread rounding across frames so 29.97 fps clips don't drift against the source.
+    let (num, den) = header.fps;
+    let at = |i: u64| (i * 1000 * den + num / 2) / num;
+    let count = (gray.len() + rgb.len()) as u64;
+    let delays_ms = (0..count).map(|i| (at(i + 1) - at(i)).max(1) as u32).collect();
+    let frames = if header.subsampling.is_some() { SourceFrames::Rgb(rgb) } else { SourceFrames::Gray(gray) };
+    Ok(DecodedMedia { frames, timing: Timing { delays_ms, loop_count: LoopCount::Forever } })
+}
+
+fn read_header(reader: &mut impl BufRead) -> Result<Header, String> {
+    let mut line = Vec::new();
+    reader.read_until(b'\n', &mut line).map_err(|e| e.to_string())?;
+    let line = String::from_utf8_lossy(&line);
+    let params = line.trim_end().strip_prefix("YUV4MPEG2").ok_or("Not a Y4M stream")?;
+
+    let (mut width, mut height, mut fps) = (0usize, 0usize, (25u64, 1u64));
+    let mut colorspace = "420jpeg";
+    let mut full_range = false;
+    for param in params.split_ascii_whitespace() {
+        let mut chars = param.chars();
+        let tag = chars.next();
+        let value = chars.as_str();
+        match tag {
+            Some('W') => width = value.parse().map_err(|_| "Bad Y4M width")?,
+            Some('H') => height = value.parse().map_err(|_| "Bad Y4M height")?,
+            Some('F') => {
+                let (num, den) = value.split_once(':').ok_or("Bad Y4M frame rate")?;
+                fps = (num.parse().map_err(|_| "Bad Y4M frame rate")?, den.parse().map_err(|_| "Bad Y4M frame rate")?);
+            }
+            Some('C') => colorspace = value,
+            Some('X') => full_range |= value.eq_ignore_ascii_case("COLORRANGE=FULL"),
+            _ => {}
+        }
+    }
+    if width == 0 || height == 0 { return Err("Y4M header is missing its dimensions".into()); }
+    if fps.0 == 0 || fps.1 == 0 { return Err("Y4M header has a zero frame rate".into()); }
+
+    // High bit depth streams carry a `p<bits>` suffix, e.g. `420p10`; `420paldv` is not one of them.
+    let (layout, bit_depth) = match colorspace.rsplit_once('p') {
+        Some((layout, bits)) if !bits.is_empty() && bits.bytes().all(|c| c.is_ascii_digit()) => {
+            (layout, bits.parse::<u32>().map_err(|_| format!("Unsupported Y4M colorspace {}", colorspace))?)
+        }
+        _ if colorspace == "mono16" => ("mono", 16),
+        _ => (colorspace, 8),
+    };
+    if !(8..=16).contains(&bit_depth) { return Err(format!("Unsupported Y4M bit depth {}", bit_depth)); }
+    let bytes_per_sample = if bit_depth > 8 { 2 } else { 1 };
+
+    let (subsampling, alpha) = match layout {
+        "420" | "420jpeg" | "420paldv" | "420mpeg2" => (Some((2, 2)), false),
+        "422" => (Some((2, 1)), false),
+        "411" => (Some((4, 1)), false),
+        "444" => (Some((1, 1)), false),
+        "444alpha" => (Some((1, 1)), true),
+        "mono" => (None, false),
+        other => return Err(format!("Unsupported Y4M colorspace {}", other)),
+    };
+
+    Ok(Header { width, height, fps, bytes_per_sample, bit_depth, subsampling, alpha, full_range })
+}
+
+/// One byte per sample, high bit depth samples shifted down.
+fn eight_bit<'a>(plane: &'a [u8], header: &Header) -> Cow<'a, [u8]> {
+    match header.bytes_per_sample {
+        1 => Cow::Borrowed(plane),
+        // Samples above the declared depth are malformed; clamp them rather than let them wrap.
+        _ => plane.chunks_exact(2)
+            .map(|s| (u16::from_le_bytes([s[0], s[1]]) >> (header.bit_depth - 8)).min(255) as u8)
+            .collect(),
+    }
+}
+
+/// Video luma is usually studio range (16-235); stretch it so black and white land on the ramp ends.
+fn range_lut(header: &Header) -> [u8; 256] {
+    let mut lut = [0u8; 256];
+    for (y, out) in lut.iter_mut().enumerate() {
+        *out = if header.full_range { y as u8 } else {
+            ((y as f32 - 16.0) * 255.0 / 219.0).round().clamp(0.0, 255.0) as u8
+        };
+    }
+    lut
+}
+
+/// Combine range-expanded luma with the Cb and Cr planes. Y4M does not record the matrix, so HD
+/// frames are taken as BT.709 and smaller ones as BT.601, the usual guess for untagged video.
+/// Transparent pixels become white, as for other sources.
+fn to_rgb(header: &Header, luma: &[u8], chroma: &[u8], alpha: &[u8]) -> RgbImage {
+    let (kr, kb) = if header.height >= 720 { (0.2126, 0.0722) } else { (0.299, 0.114) };
+    // Studio range chroma spans 16-240 around 128.
+    let scale = if header.full_range { 1.0 } else { 255.0 / 224.0 };
+    let mut chroma_lut = [0f32; 256];
+    for (c, out) in chroma_lut.iter_mut().enumerate() { *out = (c as f32 - 128.0) * scale; }
+
+    let (sx, sy) = header.subsampling.unwrap_or((1, 1));
+    let chroma_w = header.width.div_ceil(sx);
+    let (cb, cr) = chroma.split_at(header.chroma_samples());
+    let channel = |v: f32| v.round().clamp(0.0, 255.0) as u8;
+    let mut rgb = Vec::with_capacity(luma.len() * 3);
+    for (i, &y) in luma.iter().enumerate() {
+        if alpha.get(i).is_some_and(|&a| a < 128) {
+            rgb.extend([255; 3]);
+            continue;
+        }
+        let c = (i / header.width / sy) * chroma_w + (i % header.width) / sx;
+        let (y, cb, cr) = (y as f32, chroma_lut[cb[c] as usize], chroma_lut[cr[c] as usize]);
+        let r = y + 2.0 * (1.0 - kr) * cr;
+        let b = y + 2.0 * (1.0 - kb) * cb;
+        let g = (y - kr * r - kb * b) / (1.0 - kr - kb);
+        rgb.extend([channel(r), channel(g), channel(b)]);
+    }
+    RgbImage::from_raw(header.width as u32, header.height as u32, rgb).expect("rgb buffer matches header dimensions")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::LoadToken;
+
+    fn parse(line: &str) -> Result<Header, String> {
+        read_header(&mut line.as_bytes())
+    }
+
+    #[test]
+    fn reads_an_8_bit_420_header() {
+        let header = parse("YUV4MPEG2 W6 H4 F30000:1001 Ip A1:1 C420jpeg\n").unwrap();
+        assert_eq!((header.width, header.height), (6, 4));
+        assert_eq!(header.fps, (30000, 1001));
+        assert_eq!((header.bytes_per_sample, header.bit_depth), (1, 8));
+        assert_eq!((header.subsampling, header.chroma_samples()), (Some((2, 2)), 3 * 2));
+        assert!(!header.full_range);
+    }
+
+    #[test]
+    fn defaults_to_25_fps_420() {
+        let header = parse("YUV4MPEG2 W5 H3\n").unwrap();
+        assert_eq!(header.fps, (25, 1));
+        assert_eq!(header.chroma_samples(), 3 * 2);
+    }
+
+    #[test]
+    fn reads_high_bit_depth_and_full_range() {
+        let header = parse("YUV4MPEG2 W4 H2 F25:1 C444p10 XCOLORRANGE=FULL\n").unwrap();
+        assert_eq!((header.bytes_per_sample, header.bit_depth), (2, 10));
+        assert_eq!((header.subsampling, header.chroma_samples()), (Some((1, 1)), 4 * 2));
+        assert!(header.full_range);
+
+        let mono = parse("YUV4MPEG2 W4 H2 Cmono16\n").unwrap();
+        assert_eq!((mono.bit_depth, mono.subsampling), (16, None));
+    }
+
+    #[test]
+    fn tells_paldv_from_a_bit_depth() {
+        let header = parse("YUV4MPEG2 W4 H2 C420paldv\n").unwrap();
+        assert_eq!(header.bit_depth, 8);
+        assert_eq!(header.chroma_samples(), 2);
+    }
+
+    #[test]
+    fn rejects_bad_headers() {
+        assert_eq!(parse("RIFF W4 H2\n").err().unwrap(), "Not a Y4M stream");
+        assert_eq!(parse("YUV4MPEG2 W4\n").err().unwrap(), "Y4M header is missing its dimensions");
+        assert_eq!(parse("YUV4MPEG2 W4 H2 F0:1\n").err().unwrap(), "Y4M header has a zero frame rate");
+        assert_eq!(parse("YUV4MPEG2 W4 H2 C420p4\n").err().unwrap(), "Unsupported Y4M bit depth 4");
+        assert_eq!(parse("YUV4MPEG2 W4 H2 C410\n").err().unwrap(), "Unsupported Y4M colorspace 410");
+    }
+
+    fn decode_stream(name: &str, stream: &[u8]) -> Result<DecodedMedia, String> {
+        decode_stream_within(name, stream, MAX_SOURCE_BYTES)
+    }
+
+    fn decode_stream_within(name: &str, stream: &[u8], max_bytes: usize) -> Result<DecodedMedia, String> {
+        let path = std::env::temp_dir().join(format!("ascii-studio-{}-{}.y4m", std::process::id(), name));
+        std::fs::write(&path, stream).unwrap();
+        let decoded = decode_within(path.to_str().unwrap(), &LoadMonitor::new(LoadToken::default(), |_| {}), max_bytes);
+        let _ = std::fs::remove_file(&path);
+        decoded
+    }
+
+    #[test]
+    fn clamps_samples_above_the_declared_depth() {
+        let mut stream = b"YUV4MPEG2 W2 H2 F25:1 C420p10\nFRAME\n".to_vec();
+        stream.extend([0xFF; 8]);
+        // Neutral 10-bit chroma, 512 little endian.
+        stream.extend([0x00, 0x02, 0x00, 0x02]);
+        match decode_stream("overflow", &stream).unwrap().frames {
+            SourceFrames::Rgb(frames) => assert_eq!(frames[0].as_raw(), &[255; 12]),
+            SourceFrames::Gray(_) => panic!("420 streams decode to RGB"),
+        }
+    }
+
+    #[test]
+    fn converts_chroma_to_rgb() {
+        // BT.601 red in full range, then a pixel at the studio range black point.
+        let mut stream = b"YUV4MPEG2 W1 H1 F25:1 C444 XCOLORRANGE=FULL\nFRAME\n".to_vec();
+        stream.extend([76, 85, 255]);
+        match decode_stream("red", &stream).unwrap().frames {
+            SourceFrames::Rgb(frames) => assert_eq!(frames[0].as_raw(), &[254, 0, 0]),
+            SourceFrames::Gray(_) => panic!("444 streams decode to RGB"),
+        }
+
+        let mut stream = b"YUV4MPEG2 W1 H1 F25:1 C444\nFRAME\n".to_vec();
+        stream.extend([16, 128, 128]);
+        match decode_stream("black", &stream).unwrap().frames {
+            SourceFrames::Rgb(frames) => assert_eq!(frames[0].as_raw(), &[0, 0, 0]),
+            SourceFrames::Gray(_) => panic!("444 streams decode to RGB"),
+        }
+    }
+
+    #[test]
+    fn keeps_mono_streams_gray() {
+        let mut stream = b"YUV4MPEG2 W2 H1 F25:1 Cmono\nFRAME\n".to_vec();
+        stream.extend([16, 235]);
+        match decode_stream("mono", &stream).unwrap().frames {
+            SourceFrames::Gray(frames) => assert_eq!(frames[0].as_raw(), &[0, 255]),
+            SourceFrames::Rgb(_) => panic!("mono streams decode to gray"),
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_frames() {
+        let mut stream = b"YUV4MPEG2 W2 H2 F25:1 C420jpeg\nFRAME\n".to_vec();
+        stream.extend([0; 5]);
+        assert_eq!(decode_stream("truncated", &stream).err().unwrap(), "Truncated Y4M frame");
+    }
+
+    #[test]
+    fn refuses_clips_over_the_memory_cap() {
+        let mut stream = b"YUV4MPEG2 W2 H1 F25:1 Cmono\n".to_vec();
+        for _ in 0..3 { stream.extend(b"FRAME\n\x10\xEB"); }
+        assert_eq!(decode_stream_within("capped", &stream, 6).unwrap().frames.len(), 3);
+        assert_eq!(
+            decode_stream_within("over", &stream, 5).err().unwrap(),
+            "Y4M clip is longer than 2 frames at 2x1; trim it or scale it down, e.g. with ffmpeg -vf scale=480:-2"
+        );
+    }
+}
@@ -7,7 +7,7 @@ import {
   useRef,
   useLayoutEffect,
 } from "react";
-import { invoke } from "@tauri-apps/api/core";
+import { Channel, invoke } from "@tauri-apps/api/core";
 import { open, save } from "@tauri-apps/plugin-dialog";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import {
@@ -19,10 +19,134 @@ import {
   Minus,
   Square,
   Cpu,
+  Type,
 } from "lucide-react";
 import { LoaderIcon } from "./components/LoaderIcon";
+import { showFrame } from "./ansi";
 import "./App.css";
 
+type LoopCount = "forever" | { times: number };
+
+type LoadProgress =
+  | { stage: "decoding"; frames: number; fraction: number; etaMs: number | null }
+  | { stage: "caching"; built: number; total: number; etaMs: number | null };
+
+const LOAD_CANCELLED = "Load cancelled";
+const STALE_MEDIA = "Stale media";
+
+const describeProgress = (p: LoadProgress | null) => {
+  if (!p) return "DECODING CORE...";
+  const eta = p.etaMs !== null ? ` · ~${Math.ceil(p.etaMs / 1000)}S LEFT` : "";
+  return p.stage === "decoding"
+    ? `DECODING · ${p.frames} FRAMES · ${Math.round(p.fraction * 100)}%${eta}`
+    : `BUILDING CACHE · ${p.built}/${p.total}${eta}`;
+};
+
+type Resample = "nearest" | "box" | "bilinear" | "lanczos3" | "min" | "max";
+
+const RESAMPLE_OPTIONS: [Resample, string][] = [
+  ["nearest", "NEAREST"],
+  ["box", "AREA AVERAGE"],
+  ["bilinear", "BILINEAR"],
+  ["lanczos3", "LANCZOS3"],
+  ["min", "KEEP DARK STROKES"],
+  ["max", "KEEP LIGHT STROKES"],
+];
+
+type RampPreset = "short" | "standard" | "blocks" | "digits" | "binary";
+type RampChoice = RampPreset | "custom" | "measured";
+
+type RenderMode =
+  | "ramp"
+  | "shape"
+  | "halfBlock"
+  | "quadrant"
+  | "sextant"
+  | "braille";
+
+const MODE_OPTIONS: [RenderMode, string][] = [
+  ["ramp", "BRIGHTNESS RAMP"],
+  ["shape", "SHAPE MATCH"],
+  ["halfBlock", "HALF BLOCKS (ANSI)"],
+  ["quadrant", "QUADRANTS (ANSI)"],
+  ["sextant", "SEXTANTS (ANSI)"],
+  ["braille", "BRAILLE DOTS"],
+];
+
+const DOT_MODES: RenderMode[] = ["braille"];
+const BLOCK_MODES: RenderMode[] = ["halfBlock", "quadrant", "sextant"];
+
+type Palette = "trueColor" | "xterm256" | "ansi16";
+
+type Dither =
+  | "none"
+  | "floydSteinberg"
+  | "atkinson"
+  | "jarvisJudiceNinke"
+  | "bayer4"
+  | "bayer8";
+
+const DITHER_OPTIONS: [Dither, string][] = [
+  ["none", "NONE"],
+  ["floydSteinberg", "FLOYD-STEINBERG"],
+  ["atkinson", "ATKINSON"],
+  ["jarvisJudiceNinke", "JARVIS-JUDICE-NINKE"],
+  ["bayer4", "BAYER 4X4"],
+  ["bayer8", "BAYER 8X8"],
+];
+
+const DIFFUSION: Dither[] = ["floydSteinberg", "atkinson", "jarvisJudiceNinke"];
+
+const PALETTE_OPTIONS: [Palette, string][] = [
+  ["trueColor", "TRUECOLOR (24-BIT)"],
+  ["xterm256", "XTERM 256"],
+  ["ansi16", "ANSI 16"],
+];
+
+interface MeasuredRamp {
+  glyphs: string;
+  coverage: number[];
+}
+
+const RAMP_OPTIONS: [RampChoice, string][] = [
+  ["standard", "STANDARD (70)"],
+  ["short", "SHORT (10)"],
+  ["blocks", "BLOCKS"],
+  ["digits", "DIGITS"],
+  ["binary", "BINARY"],
+  ["custom", "CUSTOM"],
+];
+
+interface MediaInfo {
+  mediaId: number;
+  frameCount: number;
+  delaysMs: number[];
+  loopCount: LoopCount;
+}
+
+/** An `.ascii` file opened for playback; frames are kept as they were rendered. */
+interface SavedAnimation {
+  columns: number;
+  rows: number;
+  frameCount: number;
+  delaysMs: number[];
+  loopCount: LoopCount;
+  source: string;
+  ramp: string;
+  data: number[];
+  offsets: number[];
+}
+
+type ExportFormat = "text" | "native" | "cast" | "html" | "svg";
+
+const EXPORT_FORMATS: [ExportFormat, string, string][] = [
+  ["text", "PLAIN TEXT", "txt"],
+  ["native", "ASCII STUDIO ANIMATION", "ascii"],
+  ["cast", "ASCIINEMA CAST", "cast"],
+  ["html", "HTML PLAYER", "html"],
+  ["svg", "SVG", "svg"],
+];
+
 function App() {
   const appWindow = getCurrentWindow();
   const [gifLoaded, setGifLoaded] = useState(false);
@@ -31,9 +155,32 @@ function App() {
   const [width, setWidth] = useState(100);
   const [brightness, setBrightness] = useState(0);
   const [contrast, setContrast] = useState(1.0);
+  const [filter, setFilter] = useState<Resample>("nearest");
+  const [sequenceFps, setSequenceFps] = useState(24);
+  const [cellAspect, setCellAspect] = useState(0.5);
+  const [ramp, setRamp] = useState<RampChoice>("standard");
+  const [measuredRamp, setMeasuredRamp] = useState<MeasuredRamp | null>(null);
+  const [customRamp, setCustomRamp] = useState("@%#*+=-:. ");
+  const [invert, setInvert] = useState(false);
+  const [mode, setMode] = useState<RenderMode>("ramp");
+  const [glyphFont, setGlyphFont] = useState("");
+  const [edges, setEdges] = useState(false);
+  const [edgeThreshold, setEdgeThreshold] = useState(0.3);
+  const [threshold, setThreshold] = useState(128);
+  const [dither, setDither] = useState<Dither>("none");
+  const [stableDither, setStableDither] = useState(true);
+  const [color, setColor] = useState(false);
+  const [palette, setPalette] = useState<Palette>("trueColor");
+  const [exportFormat, setExportFormat] = useState<ExportFormat>("text");
+  const [compressExport, setCompressExport] = useState(true);
+  const [svgFont, setSvgFont] = useState("");
+  // Path of the `.ascii` animation being replayed instead of a source.
+  const [openedAnimation, setOpenedAnimation] = useState("");
 
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState("");
+  const [loadProgress, setLoadProgress] = useState<LoadProgress | null>(null);
+  const [activeMediaId, setActiveMediaId] = useState(0);
 
   const viewportRef = useRef<HTMLDivElement>(null);
   const asciiRef = useRef<HTMLPreElement>(null);
@@ -41,20 +188,83 @@ function App() {
   // High-performance state management
   const isUpdating = useRef(false);
   const pendingUpdate = useRef(false);
-  const paramsRef = useRef({ width, brightness, contrast });
+  const paramsRef = useRef({
+    width,
+    brightness,
+    contrast,
+    filter,
+    cellAspect,
+    ramp,
+    customRamp,
+    measuredRamp,
+    invert,
+    mode,
+    edges,
+    edgeThreshold,
+    threshold,
+    dither,
+    stableDither,
+    color,
+    palette,
+  });
   const asciiBuffer = useRef<Uint8Array | null>(null);
-  const frameMetadata = useRef({ count: 0, size: 0, rawW: 0, rawH: 0 });
+  const frameOffsets = useRef<number[]>([]);
+  const frameMetadata = useRef({ count: 0, rawW: 0, rawH: 0 });
   const decoder = useRef(new TextDecoder());
   const animationFrameId = useRef<number | null>(null);
   const lastFrameTime = useRef<number>(0);
   const currentFrameIdx = useRef<number>(0);
+  const timing = useRef<{ delaysMs: number[]; loopCount: LoopCount }>({
+    delaysMs: [],
+    loopCount: "forever",
+  });
+  const playsCompleted = useRef<number>(0);
+  const mediaId = useRef<number>(0);
 
   const isInteractive = useRef(false);
   const debounceTimer = useRef<number | null>(null);
+  // Cell aspect picked on the slider but not yet sent; each new aspect rebuilds every frame's tensor.
+  const pendingCellAspect = useRef<number | null>(null);
 
   useEffect(() => {
-    paramsRef.current = { width, brightness, contrast };
-  }, [width, brightness, contrast]);
+    paramsRef.current = {
+      width,
+      brightness,
+      contrast,
+      filter,
+      cellAspect,
+      ramp,
+      customRamp,
+      measuredRamp,
+      invert,
+      mode,
+      edges,
+      edgeThreshold,
+      threshold,
+      dither,
+      stableDither,
+      color,
+      palette,
+    };
+  }, [
+    width,
+    brightness,
+    contrast,
+    filter,
+    cellAspect,
+    ramp,
+    customRamp,
+    measuredRamp,
+    invert,
+    mode,
+    edges,
+    edgeThreshold,
+    threshold,
+    dither,
+    stableDither,
+    color,
+    palette,
+  ]);
 
   // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
   // CORE OPERATIONS
@@ -85,33 +295,37 @@ function App() {
     async (frameIdx: number) => {
       if (!gifLoaded) return;
       try {
-        const b64 = await invoke<string>("apply_adjustments_to_preview", {
-          brightness: paramsRef.current.brightness,
-          contrast: paramsRef.current.contrast,
-          frameIndex: frameIdx,
-        });
-        setAdjustedPreviewUrl(b64);
+        const preview = await invoke<{ mediaId: number; url: string }>(
+          "apply_adjustments_to_preview",
+          {
+            mediaId: mediaId.current,
+            brightness: paramsRef.current.brightness,
+            contrast: paramsRef.current.contrast,
+            frameIndex: frameIdx,
+          },
+        );
+        if (preview.mediaId === mediaId.current)
+          setAdjustedPreviewUrl(preview.url);
       } catch (e) {
-        console.error("Preview error:", e);
+        if (String(e) !== STALE_MEDIA) console.error("Preview error:", e);
       }
     },
     [gifLoaded],
   );
 
   const renderFrame = useCallback(
-    (idx: number, bufferOverride?: Uint8Array) => {
+    (idx: number, bufferOverride?: Uint8Array, offsetsOverride?: number[]) => {
       const buffer = bufferOverride || asciiBuffer.current;
+      const offsets = offsetsOverride || frameOffsets.current;
       if (!buffer || !asciiRef.current || frameMetadata.current.count === 0)
         return;
 
-      const { count, size } = frameMetadata.current;
-      const safeIdx = idx % count;
-      const isSingleFrameBuffer = buffer.length === size;
-      const start = isSingleFrameBuffer ? 0 : safeIdx * size;
-      const end = start + size;
+      const safeIdx = idx % frameMetadata.current.count;
+      const isSingleFrameBuffer = offsets.length === 2;
+      const slot = isSingleFrameBuffer ? 0 : safeIdx;
 
-      const slice = buffer.subarray(start, end);
-      asciiRef.current.textContent = decoder.current.decode(slice);
+      const slice = buffer.subarray(offsets[slot], offsets[slot + 1]);
+      showFrame(asciiRef.current, decoder.current.decode(slice));
       currentFrameIdx.current = safeIdx;
 
       calculateAndApplyScale();
@@ -120,9 +334,57 @@ function App() {
     [calculateAndApplyScale, updatePreview],
   );
 
+  /**
+   * Measure a frame at its natural size so the viewport can scale it to fit.
+   */
+  const measureFrame = useCallback((data: Uint8Array, offsets: number[]) => {
+    if (!asciiRef.current) return;
+    const originalTransform = asciiRef.current.style.transform;
+    const originalHtml = asciiRef.current.innerHTML;
+
+    asciiRef.current.style.transform = "none";
+    showFrame(
+      asciiRef.current,
+      decoder.current.decode(data.subarray(offsets[0], offsets[1])),
+    );
+
+    frameMetadata.current.rawW = asciiRef.current.scrollWidth;
+    frameMetadata.current.rawH = asciiRef.current.scrollHeight;
+
+    asciiRef.current.style.transform = originalTransform;
+    asciiRef.current.innerHTML = originalHtml;
+  }, []);
+
+  /**
+   * The render settings the backend expects, from the current controls.
+   */
+  const renderSettings = useCallback(() => {
+    const p = paramsRef.current;
+    return {
+      width: p.width,
+      filter: p.filter,
+      brightness: p.brightness,
+      contrast: p.contrast,
+      ramp:
+        p.ramp === "custom"
+          ? { custom: p.customRamp }
+          : p.ramp === "measured" && p.measuredRamp
+            ? { measured: p.measuredRamp }
+            : { preset: p.ramp === "measured" ? "standard" : p.ramp },
+      invert: p.invert,
+      mode: p.mode,
+      edgeThreshold: p.edges ? p.edgeThreshold : null,
+      threshold: p.threshold,
+      dither: p.dither,
+      stableDither: p.stableDither,
+      color: p.color,
+      palette: p.palette,
+    };
+  }, []);
+
   const convert = useCallback(
     async (onlyFrame?: number) => {
-      if (!gifLoaded) return;
+      if (!gifLoaded || openedAnimation) return;
       if (isUpdating.current) {
         pendingUpdate.current = true;
         return;
@@ -132,73 +394,97 @@ function App() {
       try {
         while (true) {
           pendingUpdate.current = false;
-          const { width: w, brightness: b, contrast: c } = paramsRef.current;
-
-          const response = await invoke<[number, number[]]>(
-            "convert_gif_to_ascii",
-            {
-              width: w,
-              brightness: b,
-              contrast: c,
-              onlyFrame: onlyFrame !== undefined ? onlyFrame : null,
-            },
-          );
-
-          const rawData = response[1];
-          const data = new Uint8Array(rawData);
-
-          if (data.length > 0) {
+          const response = await invoke<{
+            mediaId: number;
+            height: number;
+            data: number[];
+            offsets: number[];
+          }>("convert_gif_to_ascii", {
+            mediaId: mediaId.current,
+            settings: renderSettings(),
+            onlyFrame: onlyFrame !== undefined ? onlyFrame : null,
+          });
+
+          const data = new Uint8Array(response.data);
+          const offsets = response.offsets;
+
+          if (response.mediaId === mediaId.current && data.length > 0) {
             // Perform ONE measurement to get true character dimensions
-            if (asciiRef.current) {
-              const originalTransform = asciiRef.current.style.transform;
-              const originalText = asciiRef.current.textContent;
-
-              asciiRef.current.style.transform = "none";
-              asciiRef.current.textContent = decoder.current.decode(
-                data.subarray(
-                  0,
-                  data.length /
-                    (onlyFrame !== undefined ? 1 : frameMetadata.current.count),
-                ),
-              );
-
-              frameMetadata.current.rawW = asciiRef.current.scrollWidth;
-              frameMetadata.current.rawH = asciiRef.current.scrollHeight;
-
-              asciiRef.current.style.transform = originalTransform;
-              asciiRef.current.textContent = originalText;
-            }
+            measureFrame(data, offsets);
 
             if (onlyFrame !== undefined) {
-              frameMetadata.current.size = data.length;
-              renderFrame(onlyFrame, data);
+              renderFrame(onlyFrame, data, offsets);
             } else {
               asciiBuffer.current = data;
-              frameMetadata.current.size =
-                data.length / frameMetadata.current.count;
+              frameOffsets.current = offsets;
               renderFrame(currentFrameIdx.current);
             }
           }
           if (!pendingUpdate.current) break;
         }
       } catch (e) {
-        setError(String(e));
+        if (String(e) !== STALE_MEDIA) setError(String(e));
       } finally {
         isUpdating.current = false;
       }
     },
-    [gifLoaded, renderFrame],
+    [gifLoaded, openedAnimation, renderFrame, measureFrame, renderSettings],
   );
 
   // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
   // EVENT HANDLERS
   // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+  const loadWith = async (command: string, args: Record<string, unknown>) => {
+    setLoading(true);
+    setError("");
+    setLoadProgress(null);
+    const onProgress = new Channel<LoadProgress>();
+    onProgress.onmessage = setLoadProgress;
+    const info = await invoke<MediaInfo>(command, {
+      ...args,
+      width: paramsRef.current.width,
+      cellAspect: paramsRef.current.cellAspect,
+      onProgress,
+    });
+    if (mediaId.current !== 0)
+      invoke("close_media", { mediaId: mediaId.current }).catch(() => {});
+    mediaId.current = info.mediaId;
+    frameMetadata.current.count = info.frameCount;
+    timing.current = {
+      delaysMs: info.delaysMs,
+      loopCount: info.loopCount,
+    };
+    playsCompleted.current = 0;
+    currentFrameIdx.current = 0;
+    setActiveMediaId(info.mediaId);
+    setOpenedAnimation("");
+    setGifLoaded(true);
+    setLoading(false);
+  };
+
   const handleOpen = async () => {
     try {
       const selected = await open({
         multiple: false,
-        filters: [{ name: "GIF Images", extensions: ["gif"] }],
+        filters: [
+          {
+            name: "Images",
+            extensions: [
+              "gif",
+              "png",
+              "apng",
+              "jpg",
+              "jpeg",
+              "webp",
+              "bmp",
+              "tif",
+              "tiff",
+              "qoi",
+              "y4m",
+            ],
+          },
+        ],
       });
       if (selected) {
         const path = Array.isArray(selected)
@@ -207,37 +493,169 @@ function App() {
             ? selected
             : null;
         if (!path) return;
-        setLoading(true);
-        setError("");
-        const count = await invoke<number>("load_gif", { path });
-        frameMetadata.current.count = count;
-        setGifLoaded(true);
-        setLoading(false);
+        await loadWith("load_media", { path });
       }
+    } catch (e) {
+      if (String(e) !== LOAD_CANCELLED) setError(String(e));
+      setLoading(false);
+    }
+  };
+
+  const handleCancelLoad = () => invoke("cancel_load");
+
+  const handleOpenAnimation = async () => {
+    try {
+      const path = await open({
+        multiple: false,
+        filters: [{ name: "ASCII Studio Animation", extensions: ["ascii"] }],
+      });
+      if (typeof path !== "string") return;
+      const animation = await invoke<SavedAnimation>("open_animation", {
+        path,
+      });
+      if (mediaId.current !== 0)
+        invoke("close_media", { mediaId: mediaId.current }).catch(() => {});
+      mediaId.current = 0;
+      const data = new Uint8Array(animation.data);
+      asciiBuffer.current = data;
+      frameOffsets.current = animation.offsets;
+      frameMetadata.current.count = animation.frameCount;
+      timing.current = {
+        delaysMs: animation.delaysMs,
+        loopCount: animation.loopCount,
+      };
+      playsCompleted.current = 0;
+      currentFrameIdx.current = 0;
+      setAdjustedPreviewUrl("");
+      setOpenedAnimation(path);
+      setGifLoaded(true);
+      measureFrame(data, animation.offsets);
+      renderFrame(0);
     } catch (e) {
       setError(String(e));
+    }
+  };
+
+  const handleOpenSequence = async () => {
+    try {
+      const selected = await open({ directory: true, multiple: false });
+      if (typeof selected !== "string") return;
+      await loadWith("load_sequence", {
+        pattern: selected,
+        fps: sequenceFps,
+        letterbox: true,
+      });
+    } catch (e) {
+      if (String(e) !== LOAD_CANCELLED) setError(String(e));
       setLoading(false);
     }
   };
 
+  const applyPendingCellAspect = async () => {
+    const aspect = pendingCellAspect.current;
+    if (aspect === null) return;
+    pendingCellAspect.current = null;
+    try {
+      const applied = await invoke<number>("set_cell_aspect", {
+        mediaId: mediaId.current,
+        aspect,
+      });
+      setCellAspect(applied);
+      paramsRef.current.cellAspect = applied;
+    } catch (e) {
+      if (String(e) !== STALE_MEDIA) setError(String(e));
+    }
+  };
+
+  const handleFontAspect = async () => {
+    try {
+      const fontPath = await open({
+        multiple: false,
+        filters: [{ name: "Fonts", extensions: ["ttf", "otf", "ttc"] }],
+      });
+      if (typeof fontPath !== "string") return;
+      pendingCellAspect.current = null;
+      const applied = await invoke<number>("set_cell_aspect_from_font", {
+        mediaId: mediaId.current,
+        fontPath,
+        lineSpacing: null,
+      });
+      setCellAspect(applied);
+      paramsRef.current.cellAspect = applied;
+      convert();
+    } catch (e) {
+      if (String(e) !== STALE_MEDIA) setError(String(e));
+    }
+  };
+
+  const handleMeasureRamp = async () => {
+    try {
+      const fontPath = await open({
+        multiple: false,
+        filters: [{ name: "Fonts", extensions: ["ttf", "otf", "ttc"] }],
+      });
+      if (typeof fontPath !== "string") return;
+      const measured = await invoke<MeasuredRamp>("build_ramp", {
+        fontPath,
+        candidates: null,
+        levels: null,
+      });
+      handleParamChange(() => {
+        setMeasuredRamp(measured);
+        setRamp("measured");
+        paramsRef.current.measuredRamp = measured;
+        paramsRef.current.ramp = "measured";
+      });
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const handleGlyphFont = async () => {
+    try {
+      const fontPath = await open({
+        multiple: false,
+        filters: [{ name: "Fonts", extensions: ["ttf", "otf", "ttc"] }],
+      });
+      if (typeof fontPath !== "string") return;
+      await invoke("set_glyph_font", { fontPath });
+      setGlyphFont(fontPath.split(/[\\/]/).pop() ?? fontPath);
+      convert();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
   const handleDownload = async () => {
     if (!asciiBuffer.current) return;
     try {
+      const [, name, extension] =
+        EXPORT_FORMATS.find(([format]) => format === exportFormat) ??
+        EXPORT_FORMATS[0];
       const path = await save({
-        filters: [{ name: "Text File", extensions: ["txt"] }],
-        defaultPath: "ascii_art.txt",
+        filters: [{ name, extensions: [extension] }],
+        defaultPath: `ascii_art.${extension}`,
       });
-      if (path) {
-        const { count, size } = frameMetadata.current;
-        const frames: string[] = [];
-        for (let i = 0; i < count; i++) {
-          frames.push(
-            decoder.current.decode(
-              asciiBuffer.current.subarray(i * size, (i + 1) * size),
-            ),
-          );
-        }
-        await invoke("save_ascii_to_file", { path, frames });
+      if (!path) return;
+      const options = {
+        compress: compressExport,
+        fontFamily: svgFont.trim() || null,
+      };
+      if (openedAnimation) {
+        await invoke("export_animation", {
+          source: openedAnimation,
+          format: exportFormat,
+          options,
+          path,
+        });
+      } else {
+        await invoke("export", {
+          mediaId: mediaId.current,
+          settings: renderSettings(),
+          format: exportFormat,
+          options,
+          path,
+        });
       }
     } catch (e) {
       setError(String(e));
@@ -249,22 +667,42 @@ function App() {
     updater();
     convert(currentFrameIdx.current);
     if (debounceTimer.current) clearTimeout(debounceTimer.current);
-    debounceTimer.current = window.setTimeout(() => {
+    debounceTimer.current = window.setTimeout(async () => {
       isInteractive.current = false;
+      await applyPendingCellAspect();
       convert();
     }, 150);
   };
 
   useEffect(() => {
     if (gifLoaded) convert();
-  }, [gifLoaded, convert]);
+  }, [gifLoaded, activeMediaId, convert]);
 
   useEffect(() => {
     if (!gifLoaded || frameMetadata.current.count <= 1) return;
     const animate = (time: number) => {
-      if (!isInteractive.current && time - lastFrameTime.current > 100) {
+      const { delaysMs, loopCount } = timing.current;
+      const delay = delaysMs[currentFrameIdx.current] ?? 100;
+      const finished =
+        loopCount !== "forever" && playsCompleted.current >= loopCount.times;
+      if (
+        !isInteractive.current &&
+        !finished &&
+        time - lastFrameTime.current > delay
+      ) {
         lastFrameTime.current = time;
-        renderFrame(currentFrameIdx.current + 1);
+        const next = currentFrameIdx.current + 1;
+        if (next >= frameMetadata.current.count) {
+          playsCompleted.current += 1;
+          if (
+            loopCount !== "forever" &&
+            playsCompleted.current >= loopCount.times
+          ) {
+            animationFrameId.current = requestAnimationFrame(animate);
+            return;
+          }
+        }
+        renderFrame(next);
       }
       animationFrameId.current = requestAnimationFrame(animate);
     };
@@ -273,7 +711,7 @@ function App() {
       if (animationFrameId.current)
         cancelAnimationFrame(animationFrameId.current);
     };
-  }, [gifLoaded, renderFrame]);
+  }, [gifLoaded, openedAnimation, renderFrame]);
 
   useLayoutEffect(() => {
     if (!viewportRef.current) return;
@@ -330,8 +768,34 @@ function App() {
                 ) : (
                   <FolderOpen size={16} />
                 )}
-                {loading ? "DECODING..." : "IMPORT GIF"}
+                {loading ? "DECODING..." : "IMPORT MEDIA"}
+              </button>
+              <button
+                onClick={handleOpenSequence}
+                disabled={loading}
+                className="flat-button secondary"
+              >
+                <FolderOpen size={16} /> IMPORT SEQUENCE
               </button>
+              <button
+                onClick={handleOpenAnimation}
+                disabled={loading}
+                className="flat-button secondary"
+              >
+                <FolderOpen size={16} /> OPEN .ASCII ANIMATION
+              </button>
+              <div className="slider-flat">
+                <div className="slider-info">
+                  <span>SEQUENCE FPS</span> <span>{sequenceFps}</span>
+                </div>
+                <input
+                  type="range"
+                  min="1"
+                  max="60"
+                  value={sequenceFps}
+                  onChange={(e) => setSequenceFps(Number(e.target.value))}
+                />
+              </div>
               <div className="preview-flat">
                 {adjustedPreviewUrl ? (
                   <img
@@ -394,18 +858,311 @@ function App() {
                   }
                 />
               </div>
+              <div className="slider-flat">
+                <div className="slider-info">
+                  <span>CELL ASPECT</span> <span>{cellAspect.toFixed(2)}</span>
+                </div>
+                <input
+                  type="range"
+                  min="0.3"
+                  max="1.0"
+                  step="0.01"
+                  value={cellAspect}
+                  disabled={!gifLoaded}
+                  onChange={(e) =>
+                    handleParamChange(() => {
+                      setCellAspect(Number(e.target.value));
+                      pendingCellAspect.current = Number(e.target.value);
+                    })
+                  }
+                />
+                <button
+                  onClick={handleFontAspect}
+                  disabled={!gifLoaded}
+                  className="flat-button secondary"
+                >
+                  MATCH FONT FILE
+                </button>
+              </div>
+              <div className="slider-flat">
+                <div className="slider-info">
+                  <span>RESAMPLING</span>
+                </div>
+                <select
+                  className="select-flat"
+                  value={filter}
+                  onChange={(e) =>
+                    handleParamChange(() =>
+                      setFilter(e.target.value as Resample),
+                    )
+                  }
+                >
+                  {RESAMPLE_OPTIONS.map(([value, label]) => (
+                    <option key={value} value={value}>
+                      {label}
+                    </option>
+                  ))}
+                </select>
+              </div>
+            </section>
+
+            <section className="control-flat">
+              <div className="label-flat">
+                <Type size={12} /> CHARACTERS
+              </div>
+              <div className="slider-flat">
+                <div className="slider-info">
+                  <span>MODE</span>
+                </div>
+                <select
+                  className="select-flat"
+                  value={mode}
+                  onChange={(e) =>
+                    handleParamChange(() =>
+                      setMode(e.target.value as RenderMode),
+                    )
+                  }
+                >
+                  {MODE_OPTIONS.map(([value, label]) => (
+                    <option key={value} value={value}>
+                      {label}
+                    </option>
+                  ))}
+                </select>
+                {DOT_MODES.includes(mode) && (
+                  <>
+                    <div className="slider-info">
+                      <span>DOT THRESHOLD</span> <span>{threshold}</span>
+                    </div>
+                    <input
+                      type="range"
+                      min="1"
+                      max="255"
+                      value={threshold}
+                      onChange={(e) =>
+                        handleParamChange(() =>
+                          setThreshold(Number(e.target.value)),
+                        )
+                      }
+                    />
+                  </>
+                )}
+                {mode === "shape" && (
+                  <button
+                    onClick={handleGlyphFont}
+                    className="flat-button secondary"
+                  >
+                    {glyphFont ? `GLYPH FONT: ${glyphFont}` : "CHOOSE GLYPH FONT"}
+                  </button>
+                )}
+              </div>
+              <div className="slider-flat">
+                <label className="toggle-flat">
+                  <input
+                    type="checkbox"
+                    checked={color}
+                    onChange={(e) =>
+                      handleParamChange(() => setColor(e.target.checked))
+                    }
+                  />
+                  SOURCE COLORS
+                </label>
+                {(color || BLOCK_MODES.includes(mode)) && (
+                  <>
+                    <div className="slider-info">
+                      <span>PALETTE</span>
+                    </div>
+                    <select
+                      className="select-flat"
+                      value={palette}
+                      onChange={(e) =>
+                        handleParamChange(() =>
+                          setPalette(e.target.value as Palette),
+                        )
+                      }
+                    >
+                      {PALETTE_OPTIONS.map(([value, label]) => (
+                        <option key={value} value={value}>
+                          {label}
+                        </option>
+                      ))}
+                    </select>
+                  </>
+                )}
+              </div>
+              {(mode === "ramp" ||
+                DOT_MODES.includes(mode) ||
+                ((color || BLOCK_MODES.includes(mode)) &&
+                  palette !== "trueColor")) && (
+                <div className="slider-flat">
+                  <div className="slider-info">
+                    <span>DITHER</span>
+                  </div>
+                  <select
+                    className="select-flat"
+                    value={dither}
+                    onChange={(e) =>
+                      handleParamChange(() =>
+                        setDither(e.target.value as Dither),
+                      )
+                    }
+                  >
+                    {DITHER_OPTIONS.map(([value, label]) => (
+                      <option key={value} value={value}>
+                        {label}
+                      </option>
+                    ))}
+                  </select>
+                  {DIFFUSION.includes(dither) && (
+                    <label className="toggle-flat">
+                      <input
+                        type="checkbox"
+                        checked={stableDither}
+                        onChange={(e) =>
+                          handleParamChange(() =>
+                            setStableDither(e.target.checked),
+                          )
+                        }
+                      />
+                      STABLE ACROSS FRAMES
+                    </label>
+                  )}
+                </div>
+              )}
+              <div className="slider-flat">
+                <div className="slider-info">
+                  <span>RAMP</span>
+                </div>
+                <select
+                  className="select-flat"
+                  value={ramp}
+                  onChange={(e) =>
+                    handleParamChange(() =>
+                      setRamp(e.target.value as RampChoice),
+                    )
+                  }
+                >
+                  {RAMP_OPTIONS.map(([value, label]) => (
+                    <option key={value} value={value}>
+                      {label}
+                    </option>
+                  ))}
+                  {measuredRamp && (
+                    <option value="measured">
+                      MEASURED ({measuredRamp.glyphs.length})
+                    </option>
+                  )}
+                </select>
+                {ramp === "custom" && (
+                  <input
+                    type="text"
+                    className="input-flat"
+                    value={customRamp}
+                    placeholder="DENSEST FIRST"
+                    onChange={(e) =>
+                      handleParamChange(() => setCustomRamp(e.target.value))
+                    }
+                  />
+                )}
+              </div>
+              <label className="toggle-flat">
+                <input
+                  type="checkbox"
+                  checked={invert}
+                  onChange={(e) =>
+                    handleParamChange(() => setInvert(e.target.checked))
+                  }
+                />
+                {BLOCK_MODES.includes(mode)
+                  ? "INVERT (NEGATIVE)"
+                  : "INVERT FOR DARK BACKGROUNDS"}
+              </label>
+              <label className="toggle-flat">
+                <input
+                  type="checkbox"
+                  checked={edges}
+                  onChange={(e) =>
+                    handleParamChange(() => setEdges(e.target.checked))
+                  }
+                />
+                OUTLINE EDGES
+              </label>
+              {edges && (
+                <div className="slider-flat">
+                  <div className="slider-info">
+                    <span>EDGE THRESHOLD</span>{" "}
+                    <span>{edgeThreshold.toFixed(2)}</span>
+                  </div>
+                  <input
+                    type="range"
+                    min="0.05"
+                    max="1.0"
+                    step="0.05"
+                    value={edgeThreshold}
+                    onChange={(e) =>
+                      handleParamChange(() =>
+                        setEdgeThreshold(Number(e.target.value)),
+                      )
+                    }
+                  />
+                </div>
+              )}
+              <button
+                onClick={handleMeasureRamp}
+                className="flat-button secondary"
+              >
+                MEASURE RAMP FROM FONT
+              </button>
             </section>
 
             <section className="control-flat">
               <div className="label-flat">
                 <Download size={12} /> EXPORT
               </div>
+              <div className="slider-flat">
+                <div className="slider-info">
+                  <span>FORMAT</span>
+                </div>
+                <select
+                  className="select-flat"
+                  value={exportFormat}
+                  onChange={(e) =>
+                    setExportFormat(e.target.value as ExportFormat)
+                  }
+                >
+                  {EXPORT_FORMATS.map(([value, label]) => (
+                    <option key={value} value={value}>
+                      {label}
+                    </option>
+                  ))}
+                </select>
+                {exportFormat === "html" && (
+                  <label className="toggle-flat">
+                    <input
+                      type="checkbox"
+                      checked={compressExport}
+                      onChange={(e) => setCompressExport(e.target.checked)}
+                    />
+                    COMPRESS FRAMES
+                  </label>
+                )}
+                {exportFormat === "svg" && (
+                  <input
+                    type="text"
+                    className="input-flat"
+                    value={svgFont}
+                    placeholder="MONOSPACE FONT FAMILY"
+                    onChange={(e) => setSvgFont(e.target.value)}
+                  />
+                )}
+              </div>
               <button
                 onClick={handleDownload}
                 disabled={!gifLoaded}
                 className="flat-button secondary"
               >
-                <Download size={16} /> EXPORT ASCII.TXT
+                <Download size={16} /> EXPORT
+                {openedAnimation ? " SAVED ANIMATION" : ""}
               </button>
             </section>
           </div>
@@ -416,7 +1173,12 @@ function App() {
             {loading && (
               <div className="loader-flat-overlay">
                 <LoaderIcon />
-                <div className="loader-status">DECODING CORE...</div>
+                <div className="loader-status">
+                  {describeProgress(loadProgress)}
+                </div>
+                <button className="loader-cancel" onClick={handleCancelLoad}>
+                  CANCEL
+                </button>
               </div>
             )}
             <pre className="ascii-render" ref={asciiRef} />
@@ -0,0 +1,103 @@
+// Copyright (c) 2026 Randall Rosas (Slategray). All rights reserved.
+
+const SGR = /\x1b\[([\d;]*)m/g;
+
+// xterm's defaults for the 16 themeable colors.
+const ANSI16 = [
+  "0,0,0",
+  "205,0,0",
+  "0,205,0",
+  "205,205,0",
+  "0,0,238",
+  "205,0,205",
+  "0,205,205",
+  "229,229,229",
+  "127,127,127",
+  "255,0,0",
+  "0,255,0",
+  "255,255,0",
+  "92,92,255",
+  "255,0,255",
+  "0,255,255",
+  "255,255,255",
+];
+
+const CUBE_LEVELS = [0, 95, 135, 175, 215, 255];
+
+/**
+ * The color of xterm-256 palette entry `index`.
+ */
+const xterm256 = (index: number) => {
+  if (index < 16) return `rgb(${ANSI16[index]})`;
+  if (index >= 232) {
+    const gray = 8 + 10 * (index - 232);
+    return `rgb(${gray},${gray},${gray})`;
+  }
+  const i = index - 16;
+  const r = CUBE_LEVELS[Math.floor(i / 36)];
+  const g = CUBE_LEVELS[Math.floor(i / 6) % 6];
+  const b = CUBE_LEVELS[i % 6];
+  return `rgb(${r},${g},${b})`;
+};
+
+const escapeHtml = (text: string) =>
+  text.replace(/&/g, "&amp;").replace(/</g, "&lt;").replace(/>/g, "&gt;");
+
+/**
+ * Convert text with ANSI color escapes into HTML spans.
+ */
+export const ansiToHtml = (text: string) => {
+  let html = "";
+  let fg: string | null = null;
+  let bg: string | null = null;
+  let last = 0;
+
+  const emit = (chunk: string) => {
+    if (!chunk) return;
+    const style =
+      (fg ? `color:${fg};` : "") + (bg ? `background:${bg};` : "");
+    html += style
+      ? `<span style="${style}">${escapeHtml(chunk)}</span>`
+      : escapeHtml(chunk);
+  };
+
+  for (const match of text.matchAll(SGR)) {
+    emit(text.slice(last, match.index));
+    last = match.index + match[0].length;
+    const codes = match[1].split(";").map(Number);
+    for (let i = 0; i < codes.length; i++) {
+      const code = codes[i];
+      if (code === 0) {
+        fg = null;
+        bg = null;
+      } else if ((code === 38 || code === 48) && codes[i + 1] === 2) {
+        const color = `rgb(${codes[i + 2]},${codes[i + 3]},${codes[i + 4]})`;
+        if (code === 38) fg = color;
+        else bg = color;
+        i += 4;
+      } else if ((code === 38 || code === 48) && codes[i + 1] === 5) {
+        if (code === 38) fg = xterm256(codes[i + 2]);
+        else bg = xterm256(codes[i + 2]);
+        i += 2;
+      } else if (code >= 30 && code <= 37) {
+        fg = xterm256(code - 30);
+      } else if (code >= 90 && code <= 97) {
+        fg = xterm256(code - 82);
+      } else if (code >= 40 && code <= 47) {
+        bg = xterm256(code - 40);
+      } else if (code >= 100 && code <= 107) {
+        bg = xterm256(code - 92);
+      }
+    }
+  }
+  emit(text.slice(last));
+  return html;
+};
+
+/**
+ * Show a rendered frame, switching to HTML only when it carries color escapes.
+ */
+export const showFrame = (el: HTMLElement, text: string) => {
+  if (text.includes("\x1b")) el.innerHTML = ansiToHtml(text);
+  else el.textContent = text;
+};